@@ -0,0 +1,61 @@
+use crate::payload::api::ApiError;
+use crate::payload::rest::DecodeError;
+use std::fmt;
+
+/// Errors returned by the `try_` helpers, and by `ApiError` and `DecodeError`
+/// when folded in with `?`.
+#[derive(Debug)]
+pub enum Error {
+    /// A value could not be encoded to, or decoded from, JSON.
+    Json(serde_json::Error),
+    Decode(DecodeError),
+    Api(ApiError),
+    /// The `X-Forwarded-For` header did not start with an ip address.
+    InvalidAddress(String),
+    /// A proxy was not a CIDR subnet, e.g. "10.0.0.0/8".
+    InvalidSubnet(String),
+    /// The `Contact` header did not hold a sip uri with an ip address host.
+    InvalidContact(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Json(e) => write!(f, "json error: {}", e),
+            Error::Decode(e) => write!(f, "{}", e),
+            Error::Api(e) => write!(f, "{}", e),
+            Error::InvalidAddress(address) => write!(f, "invalid ip address `{}`", address),
+            Error::InvalidSubnet(subnet) => write!(f, "invalid subnet `{}`", subnet),
+            Error::InvalidContact(contact) => write!(f, "invalid contact `{}`", contact),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Json(e) => Some(e),
+            Error::Decode(e) => Some(e),
+            Error::Api(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self {
+        Error::Decode(e)
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(e: ApiError) -> Self {
+        Error::Api(e)
+    }
+}
@@ -2,12 +2,16 @@
 #![allow(unused_variables)]
 #![allow(unused_imports)]
 
+pub mod error;
 pub mod payload;
 pub mod shared;
 pub mod verbs;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 use serde::{Deserialize, Serialize};
 
+pub use error::Error;
 pub use payload::*;
 pub use shared::*;
 pub use verbs::*;
@@ -0,0 +1,701 @@
+use crate::payload::ws::{
+    CallStatus, CallStatusValue, ConferenceHoldStatus, ConferenceHoldStatusValue,
+    ConferenceMuteStatus, ConferenceMuteStatusValue, ListenStatus, ListenStatusValue, MuteStatus,
+    MuteStatusValue, Record, RecordData, SipRequest, SipRequestData, Whisper,
+};
+use crate::verbs::amd::Amd;
+use crate::verbs::dub::DubData;
+use crate::verbs::message::Message;
+use crate::verbs::play_say::PlaySay;
+use crate::verbs::tag::Tag;
+use crate::verbs::target::Target;
+use crate::verbs::verb::Verb;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Builds requests for the jambonz REST API without tying callers to an http client.
+/// Send the returned `ApiRequest` with any client, then decode the reply with `parse`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JambonzApi {
+    /// Base url of the api including the version, e.g. "https://jambonz.example.com/v1"
+    pub base_url: String,
+    pub account_sid: String,
+    /// Read from config but never serialized, so it cannot leak into logs or replies.
+    #[serde(skip_serializing)]
+    pub api_key: String,
+}
+
+impl JambonzApi {
+    pub fn new(base_url: String, account_sid: String, api_key: String) -> JambonzApi {
+        JambonzApi {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            account_sid,
+            api_key,
+        }
+    }
+
+    /// `POST /Accounts/{account_sid}/Calls`
+    pub fn create_call(&self, create_call: &CreateCall) -> serde_json::Result<ApiRequest> {
+        Ok(self
+            .request(HttpMethod::Post, "/Calls")
+            .body(serde_json::to_string(create_call)?))
+    }
+
+    /// `PUT /Accounts/{account_sid}/Calls/{call_sid}`
+    pub fn update_call(
+        &self,
+        call_sid: &str,
+        update_call: &UpdateCall,
+    ) -> serde_json::Result<ApiRequest> {
+        Ok(self
+            .request(HttpMethod::Put, &format!("/Calls/{}", call_sid))
+            .body(serde_json::to_string(update_call)?))
+    }
+
+    /// `GET /Accounts/{account_sid}/Calls`
+    pub fn list_calls(&self, filter: &ListCalls) -> ApiRequest {
+        let query = filter.query();
+        if query.is_empty() {
+            self.request(HttpMethod::Get, "/Calls")
+        } else {
+            self.request(HttpMethod::Get, &format!("/Calls?{}", query))
+        }
+    }
+
+    /// `GET /Accounts/{account_sid}/Calls/{call_sid}`
+    pub fn get_call(&self, call_sid: &str) -> ApiRequest {
+        self.request(HttpMethod::Get, &format!("/Calls/{}", call_sid))
+    }
+
+    /// `DELETE /Accounts/{account_sid}/Calls/{call_sid}`
+    pub fn delete_call(&self, call_sid: &str) -> ApiRequest {
+        self.request(HttpMethod::Delete, &format!("/Calls/{}", call_sid))
+    }
+
+    /// `POST /Accounts/{account_sid}/Messages`
+    pub fn create_message(&self, create_message: &CreateMessage) -> serde_json::Result<ApiRequest> {
+        Ok(self
+            .request(HttpMethod::Post, "/Messages")
+            .body(serde_json::to_string(create_message)?))
+    }
+
+    fn request(&self, method: HttpMethod, path: &str) -> ApiRequest {
+        let mut headers = HashMap::new();
+        headers.insert(
+            "Authorization".to_string(),
+            format!("Bearer {}", self.api_key),
+        );
+        ApiRequest {
+            method,
+            url: format!("{}/Accounts/{}{}", self.base_url, self.account_sid, path),
+            headers,
+            body: None,
+        }
+    }
+
+    /// Decodes a response body, turning non 2xx statuses into `ApiError::Status`.
+    pub fn parse<T: DeserializeOwned>(status: u16, body: &str) -> Result<T, ApiError> {
+        if !(200..300).contains(&status) {
+            let message = serde_json::from_str::<ApiErrorBody>(body)
+                .ok()
+                .map(|error| error.msg);
+            return Err(ApiError::Status { status, message });
+        }
+        // updateCall replies with an empty 202 body
+        let body = if body.trim().is_empty() { "null" } else { body };
+        serde_json::from_str(body).map_err(ApiError::Json)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Delete => "DELETE",
+        }
+    }
+}
+
+/// A fully formed http request, ready to hand to an http client.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ApiRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: Option<String>,
+}
+
+impl ApiRequest {
+    fn body(mut self, body: String) -> ApiRequest {
+        self.headers
+            .insert("Content-Type".to_string(), "application/json".to_string());
+        self.body = Some(body);
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    /// jambonz rejected the request, `message` is the `msg` from the error body.
+    Status {
+        status: u16,
+        message: Option<String>,
+    },
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Status {
+                status,
+                message: Some(message),
+            } => write!(f, "jambonz returned {}: {}", status, message),
+            ApiError::Status { status, .. } => write!(f, "jambonz returned {}", status),
+            ApiError::Json(e) => write!(f, "invalid response body: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApiError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    msg: String,
+}
+
+/// Body of `POST /Accounts/{account_sid}/Calls`.
+/// Either `call_hook` or `application_sid` must be set.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CreateCall {
+    pub from: String,
+    pub to: Target,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_sid: Option<String>,
+
+    /// Webhook that returns the verbs to run once the call is answered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_hook: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_status_hook: Option<String>,
+
+    /// Verbs to run once the call is answered, instead of a `call_hook`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_json: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amd: Option<Amd>,
+
+    /// Arbitrary SIP headers to add to the outbound INVITE.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    /// Ring timeout in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u16>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub tag: HashMap<String, Value>,
+}
+
+impl CreateCall {
+    pub fn new(from: String, to: Target) -> CreateCall {
+        CreateCall {
+            from,
+            to,
+            application_sid: None,
+            call_hook: None,
+            call_status_hook: None,
+            app_json: None,
+            amd: None,
+            headers: HashMap::new(),
+            timeout: None,
+            tag: HashMap::new(),
+        }
+    }
+
+    pub fn application_sid(&mut self, application_sid: Option<String>) -> &mut CreateCall {
+        self.application_sid = application_sid;
+        self
+    }
+
+    pub fn call_hook(&mut self, call_hook: Option<String>) -> &mut CreateCall {
+        self.call_hook = call_hook;
+        self
+    }
+
+    pub fn call_status_hook(&mut self, call_status_hook: Option<String>) -> &mut CreateCall {
+        self.call_status_hook = call_status_hook;
+        self
+    }
+
+    pub fn app_json(&mut self, verbs: Vec<Verb>) -> serde_json::Result<&mut CreateCall> {
+        self.app_json = Some(serde_json::to_string(&verbs)?);
+        Ok(self)
+    }
+
+    pub fn amd(&mut self, amd: Option<Amd>) -> &mut CreateCall {
+        self.amd = amd;
+        self
+    }
+
+    pub fn header(&mut self, key: String, value: String) -> &mut CreateCall {
+        self.headers.insert(key, value);
+        self
+    }
+
+    pub fn replace_headers(&mut self, headers: HashMap<String, String>) -> &mut CreateCall {
+        self.headers = headers;
+        self
+    }
+
+    pub fn timeout(&mut self, timeout: Option<u16>) -> &mut CreateCall {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn tag<V: Into<Value>>(&mut self, key: String, value: V) -> &mut CreateCall {
+        self.tag.insert(key, value.into());
+        self
+    }
+
+    pub fn build(&mut self) -> CreateCall {
+        self.clone()
+    }
+}
+
+/// Response to `POST /Accounts/{account_sid}/Calls`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CreateCallResponse {
+    #[serde(alias = "call_sid")]
+    pub sid: String,
+}
+
+/// Body of `PUT /Accounts/{account_sid}/Calls/{call_sid}`.
+/// jambonz applies one change per request.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateCall {
+    /// Redirects the call to the verbs returned by this webhook.
+    CallHook(String),
+    CallStatus(CallStatusValue),
+    MuteStatus(MuteStatusValue),
+    ConfMuteStatus(ConferenceMuteStatusValue),
+    ConfHoldStatus(ConferenceHoldStatusValue),
+    ListenStatus(ListenStatusValue),
+    Record(RecordData),
+    Whisper(Vec<PlaySay>),
+    Dub(DubData),
+    Tag(HashMap<String, Value>),
+    SipRequest(SipRequestData),
+}
+
+impl UpdateCall {
+    pub fn redirect(call_hook: String) -> UpdateCall {
+        UpdateCall::CallHook(call_hook)
+    }
+
+    pub fn hangup() -> UpdateCall {
+        UpdateCall::CallStatus(CallStatusValue::Completed)
+    }
+}
+
+impl From<CallStatus> for UpdateCall {
+    fn from(call_status: CallStatus) -> UpdateCall {
+        UpdateCall::CallStatus(call_status.data.call_status)
+    }
+}
+
+impl From<MuteStatus> for UpdateCall {
+    fn from(mute_status: MuteStatus) -> UpdateCall {
+        UpdateCall::MuteStatus(mute_status.data.mute_status)
+    }
+}
+
+impl From<ConferenceMuteStatus> for UpdateCall {
+    fn from(conference_mute_status: ConferenceMuteStatus) -> UpdateCall {
+        UpdateCall::ConfMuteStatus(conference_mute_status.data.conf_mute_status)
+    }
+}
+
+impl From<ConferenceHoldStatus> for UpdateCall {
+    fn from(conference_hold_status: ConferenceHoldStatus) -> UpdateCall {
+        UpdateCall::ConfHoldStatus(conference_hold_status.data.conf_hold_status)
+    }
+}
+
+impl From<ListenStatus> for UpdateCall {
+    fn from(listen_status: ListenStatus) -> UpdateCall {
+        UpdateCall::ListenStatus(listen_status.data.listen_status)
+    }
+}
+
+impl From<Record> for UpdateCall {
+    fn from(record: Record) -> UpdateCall {
+        UpdateCall::Record(record.data)
+    }
+}
+
+impl From<Whisper> for UpdateCall {
+    fn from(whisper: Whisper) -> UpdateCall {
+        UpdateCall::Whisper(whisper.data)
+    }
+}
+
+impl From<DubData> for UpdateCall {
+    fn from(dub_data: DubData) -> UpdateCall {
+        UpdateCall::Dub(dub_data)
+    }
+}
+
+impl From<Tag> for UpdateCall {
+    fn from(tag: Tag) -> UpdateCall {
+        UpdateCall::Tag(tag.data)
+    }
+}
+
+impl From<SipRequest> for UpdateCall {
+    fn from(sip_request: SipRequest) -> UpdateCall {
+        UpdateCall::SipRequest(sip_request.data)
+    }
+}
+
+/// Body of `POST /Accounts/{account_sid}/Messages`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CreateMessage {
+    pub from: String,
+    pub to: String,
+    pub text: String,
+
+    /// Urls of media to attach, for carriers that support MMS.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub media: Vec<String>,
+
+    /// The carrier to send through, if not the account default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+impl CreateMessage {
+    pub fn new(from: String, to: String, text: String) -> CreateMessage {
+        CreateMessage {
+            from,
+            to,
+            text,
+            media: vec![],
+            provider: None,
+        }
+    }
+
+    pub fn add_media(&mut self, url: String) -> &mut CreateMessage {
+        self.media.push(url);
+        self
+    }
+
+    pub fn provider(&mut self, provider: Option<String>) -> &mut CreateMessage {
+        self.provider = provider;
+        self
+    }
+
+    pub fn build(&mut self) -> CreateMessage {
+        self.clone()
+    }
+}
+
+impl From<Message> for CreateMessage {
+    fn from(message: Message) -> CreateMessage {
+        CreateMessage {
+            from: message.from,
+            to: message.to,
+            text: message.text,
+            media: vec![],
+            provider: message.carrier,
+        }
+    }
+}
+
+/// Response to `POST /Accounts/{account_sid}/Messages`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CreateMessageResponse {
+    #[serde(alias = "message_sid")]
+    pub sid: String,
+
+    /// The raw response from the carrier, when jambonz passes it back.
+    #[serde(alias = "providerResponse")]
+    pub provider_response: Option<Value>,
+}
+
+/// Query filters for `GET /Accounts/{account_sid}/Calls`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ListCalls {
+    pub direction: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub call_status: Option<String>,
+}
+
+impl Default for ListCalls {
+    fn default() -> ListCalls {
+        ListCalls::new()
+    }
+}
+
+impl ListCalls {
+    pub fn new() -> ListCalls {
+        ListCalls {
+            direction: None,
+            from: None,
+            to: None,
+            call_status: None,
+        }
+    }
+
+    pub fn direction(&mut self, direction: Option<String>) -> &mut ListCalls {
+        self.direction = direction;
+        self
+    }
+
+    pub fn from(&mut self, from: Option<String>) -> &mut ListCalls {
+        self.from = from;
+        self
+    }
+
+    pub fn to(&mut self, to: Option<String>) -> &mut ListCalls {
+        self.to = to;
+        self
+    }
+
+    pub fn call_status(&mut self, call_status: Option<String>) -> &mut ListCalls {
+        self.call_status = call_status;
+        self
+    }
+
+    fn query(&self) -> String {
+        [
+            ("direction", &self.direction),
+            ("from", &self.from),
+            ("to", &self.to),
+            ("callStatus", &self.call_status),
+        ]
+        .iter()
+        .filter_map(|(key, value)| {
+            value
+                .as_ref()
+                .map(|value| format!("{}={}", key, encode_query(value)))
+        })
+        .collect::<Vec<String>>()
+        .join("&")
+    }
+}
+
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// A live call, as returned by `GET /Accounts/{account_sid}/Calls`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CallInfo {
+    pub call_sid: String,
+    pub call_id: Option<String>,
+    pub account_sid: String,
+    pub application_sid: Option<String>,
+    pub direction: String,
+    pub from: String,
+    pub to: String,
+    pub call_status: String,
+    pub sip_status: Option<u16>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+#[test]
+fn create_and_update_call() {
+    use crate::verbs::target::Phone;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let mut requests = vec![];
+        for reply in [
+            "HTTP/1.1 201 Created\r\nContent-Length: 13\r\n\r\n{\"sid\":\"abc\"}",
+            "HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n",
+        ] {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = vec![];
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                head.push(line.trim_end().to_string());
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader.get_mut().write_all(reply.as_bytes()).unwrap();
+            requests.push((head, String::from_utf8(body).unwrap()));
+        }
+        requests
+    });
+
+    let send = |request: ApiRequest| -> (u16, String) {
+        let path = request.url.splitn(4, '/').nth(3).unwrap().to_string();
+        let body = request.body.unwrap_or_default();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut http = format!("{} /{} HTTP/1.1\r\n", request.method.as_str(), path);
+        for (key, value) in request.headers {
+            http.push_str(&format!("{}: {}\r\n", key, value));
+        }
+        http.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+        stream.write_all(http.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap().to_string();
+        (status, body)
+    };
+
+    let api = JambonzApi::new(
+        format!("http://{}/v1/", addr),
+        "acc-1".to_string(),
+        "secret".to_string(),
+    );
+    let create = CreateCall::new(
+        "+441234567890".to_string(),
+        Phone::new("+15551234".into()).into(),
+    )
+    .call_hook(Some("https://example.com/answer".to_string()))
+    .amd(Some(Amd::new("/amd".to_string())))
+    .header("X-Campaign".to_string(), "spring".to_string())
+    .timeout(Some(30))
+    .tag("attempt".to_string(), 2)
+    .build();
+    let (status, body) = send(api.create_call(&create).unwrap());
+    let created: CreateCallResponse = JambonzApi::parse(status, &body).unwrap();
+    assert_eq!(created.sid, "abc");
+
+    let mute = MuteStatus {
+        queue_command: false,
+        data: crate::payload::ws::MuteStatusData {
+            mute_status: MuteStatusValue::Mute,
+        },
+    };
+    let (status, body) = send(api.update_call(&created.sid, &mute.into()).unwrap());
+    let _: () = JambonzApi::parse(status, &body).unwrap();
+
+    let requests = server.join().unwrap();
+    assert_eq!(requests[0].0[0], "POST /v1/Accounts/acc-1/Calls HTTP/1.1");
+    assert!(
+        requests[0]
+            .0
+            .contains(&"Authorization: Bearer secret".to_string())
+    );
+    let sent: Value = serde_json::from_str(&requests[0].1).unwrap();
+    assert_eq!(sent["to"]["type"], "phone");
+    assert_eq!(sent["amd"]["actionHook"], "/amd");
+    assert_eq!(sent["headers"]["X-Campaign"], "spring");
+    assert_eq!(sent["tag"]["attempt"], 2);
+    assert!(serde_json::to_value(&api).unwrap().get("api_key").is_none());
+    assert_eq!(
+        requests[1].0[0],
+        "PUT /v1/Accounts/acc-1/Calls/abc HTTP/1.1"
+    );
+    assert_eq!(requests[1].1, r#"{"mute_status":"mute"}"#);
+
+    let error = JambonzApi::parse::<CreateCallResponse>(400, r#"{"msg":"invalid to"}"#);
+    match error {
+        Err(ApiError::Status { status, message }) => {
+            assert_eq!(status, 400);
+            assert_eq!(message.as_deref(), Some("invalid to"));
+        }
+        _ => panic!("expected status error"),
+    }
+
+    let filter = ListCalls::new()
+        .direction(Some("outbound".to_string()))
+        .from(Some("+44 1234".to_string()))
+        .clone();
+    assert!(
+        api.list_calls(&filter)
+            .url
+            .ends_with("/Calls?direction=outbound&from=%2B44%201234")
+    );
+    assert_eq!(
+        serde_json::to_string(&UpdateCall::hangup()).unwrap(),
+        r#"{"call_status":"completed"}"#
+    );
+}
+
+#[test]
+fn create_message() {
+    let api = JambonzApi::new(
+        "https://jambonz.example.com/v1".to_string(),
+        "acc-1".to_string(),
+        "secret".to_string(),
+    );
+    let mut message = Message::new(
+        "+441234567890".to_string(),
+        "+447700900123".to_string(),
+        "Your code is 1234".to_string(),
+    );
+    message.with_carrier(Some("voxbone".to_string()));
+    let request = api.create_message(&message.into()).unwrap();
+    assert_eq!(request.method, HttpMethod::Post);
+    assert_eq!(
+        request.url,
+        "https://jambonz.example.com/v1/Accounts/acc-1/Messages"
+    );
+    let sent: Value = serde_json::from_str(&request.body.unwrap()).unwrap();
+    assert_eq!(sent["provider"], "voxbone");
+    assert!(sent.get("media").is_none());
+
+    let response: CreateMessageResponse = JambonzApi::parse(
+        200,
+        r#"{"sid": "b1c2", "providerResponse": {"id": "vx-99"}}"#,
+    )
+    .unwrap();
+    assert_eq!(response.sid, "b1c2");
+    assert_eq!(response.provider_response.unwrap()["id"], "vx-99");
+}
@@ -1,6 +1,7 @@
 use crate::payload::ws::{
     Ack, CallStatus, Command, CommandValue, ConferenceHoldStatus, ConferenceMuteStatus,
-    ListenStatus, MuteStatus, Record, Verbs, WSRedirect, WebsocketReply, Whisper,
+    ListenStatus, LlmToolOutput, LlmUpdate, MuteStatus, Record, SipRequest, SipRequestAck,
+    Verbs, WSRedirect, WebsocketReply, Whisper,
 };
 use crate::verbs::conference::Conference;
 use crate::verbs::config::Config;
@@ -14,6 +15,7 @@ use crate::verbs::gather::{Gather, Input};
 use crate::verbs::hangup::Hangup;
 use crate::verbs::leave::Leave;
 use crate::verbs::lex::Lex;
+use crate::verbs::llm::Llm;
 use crate::verbs::message::Message;
 use crate::verbs::pause::Pause;
 use crate::verbs::play::Play;
@@ -27,6 +29,7 @@ use crate::verbs::tag::Tag;
 use crate::verbs::target::{Phone, Sip, User};
 use crate::verbs::transcribe::Transcribe;
 use crate::verbs::verb::Verb;
+use crate::error::Error;
 use log::error;
 use crate::listen::Listen;
 
@@ -46,6 +49,7 @@ pub trait VerbTrait {
     fn leave(&mut self, leave: Leave) -> &mut Self;
     fn lex(&mut self, lex: Lex) -> &mut Self;
     fn listen(&mut self, listen: Listen) -> &mut Self;
+    fn llm(&mut self, llm: Llm) -> &mut Self;
     fn message(&mut self, message: Message) -> &mut Self;
     fn pause(&mut self, pause: Pause) -> &mut Self;
     fn play_url(&mut self, url: &str) -> &mut Self;
@@ -61,6 +65,9 @@ pub trait VerbTrait {
     fn tag(&mut self, tag: Tag) -> &mut Self;
     fn transcribe(&mut self, transcribe: Transcribe) -> &mut Self;
     fn push(&mut self, verb: Verb) -> &mut Self;
+    fn with_id(&mut self, id: &str) -> &mut Self;
+    fn with_generated_ids(&mut self) -> &mut Self;
+    fn find_by_id(&self, id: &str) -> Option<&Verb>;
     fn as_ack(&mut self) -> Ack;
     fn as_ack_reply(&mut self) -> WebsocketReply;
     fn as_redirect(&mut self, queue_command: bool) -> WSRedirect;
@@ -68,8 +75,14 @@ pub trait VerbTrait {
 }
 
 impl WebsocketReply {
+    pub fn try_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Logs and replaces the reply with a placeholder if it cannot be
+    /// serialized, use `try_json` to handle the error instead.
     pub fn json(&self) -> String {
-        serde_json::to_string(self).unwrap_or_else(|e| {
+        self.try_json().unwrap_or_else(|e| {
             error!("{}", e);
             "Error serializing WebsocketReply".to_string()
         })
@@ -142,6 +155,10 @@ impl VerbTrait for Verbs {
         self.push(Verb::Listen(listen))
     }
 
+    fn llm(&mut self, llm: Llm) -> &mut Self {
+        self.push(Verb::Llm(llm))
+    }
+
     fn message(&mut self, message: Message) -> &mut Self {
         self.push(Verb::Message(message))
     }
@@ -169,12 +186,14 @@ impl VerbTrait for Verbs {
 
     fn redirect_url(&mut self, url: &str) -> &mut Self {
         self.push(Verb::Redirect(Redirect {
+            id: None,
             action_hook: url.to_string(),
         }))
     }
 
     fn say_text(&mut self, text: &str) -> &mut Self {
         let say = Say {
+            id: None,
             text: text.to_string(),
             say_loop: Some(1),
             synthesizer: None,
@@ -212,6 +231,26 @@ impl VerbTrait for Verbs {
         self
     }
 
+    /// Sets the `id` of the most recently pushed verb.
+    fn with_id(&mut self, id: &str) -> &mut Self {
+        if let Some(verb) = self.data.last_mut() {
+            verb.set_id(Some(id.to_string()));
+        }
+        self
+    }
+
+    /// Gives every verb without an `id` a generated one.
+    fn with_generated_ids(&mut self) -> &mut Self {
+        self.data.iter_mut().for_each(|verb| {
+            verb.ensure_id();
+        });
+        self
+    }
+
+    fn find_by_id(&self, id: &str) -> Option<&Verb> {
+        self.data.iter().find(|verb| verb.id() == Some(id))
+    }
+
     fn as_ack(&mut self) -> Ack {
         Ack {
             msgid: self.msg_id.to_string(),
@@ -310,6 +349,37 @@ impl Into<WebsocketReply> for MuteStatus {
         })
     }
 }
+
+impl From<LlmToolOutput> for WebsocketReply {
+    fn from(llm_tool_output: LlmToolOutput) -> WebsocketReply {
+        WebsocketReply::Command(Command {
+            command_type: CommandValue::LlmToolOutput(llm_tool_output),
+        })
+    }
+}
+
+impl From<LlmUpdate> for WebsocketReply {
+    fn from(llm_update: LlmUpdate) -> WebsocketReply {
+        WebsocketReply::Command(Command {
+            command_type: CommandValue::LlmUpdate(llm_update),
+        })
+    }
+}
+
+impl From<SipRequest> for WebsocketReply {
+    fn from(sip_request: SipRequest) -> WebsocketReply {
+        WebsocketReply::Command(Command {
+            command_type: CommandValue::SipRequest(sip_request),
+        })
+    }
+}
+
+impl From<SipRequestAck> for WebsocketReply {
+    fn from(sip_request_ack: SipRequestAck) -> WebsocketReply {
+        WebsocketReply::SipRequestAck(sip_request_ack)
+    }
+}
+
 impl Into<WebsocketReply> for Ack {
     fn into(self) -> WebsocketReply {
         let ack = self.clone().verbs.as_ack();
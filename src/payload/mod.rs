@@ -1,3 +1,6 @@
 pub mod rest;
 pub mod ws;
-pub mod builder;
\ No newline at end of file
+pub mod builder;
+pub mod api;
+pub mod session;
+pub mod router;
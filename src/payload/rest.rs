@@ -1,27 +1,201 @@
-//todo Add Dialogflow Request
-//todo Add RasaResult Request
-//todo Add AmdResult Request
 
+use crate::payload::ws::{SipMethod, SipRequestAck};
 use crate::shared::shared::{Direction, SIPStatus};
+use crate::verbs::conference::ConferenceStatusEvent;
+use crate::verbs::message::Message;
+use crate::error::Error;
 use crate::TenantType;
 use ip_in_subnet::iface_in_subnet;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use log::error;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::LazyLock;
+use std::time::Duration;
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
-//todo add DialogFlow
-//todo add rasaResult
 pub enum Request {
     Initial(InitialRequest),
+    SipRequestWithinDialog(SipRequestWithinDialog),
     Dial(SubsequentDialRequest),
     Queue(SubsequentQueueRequest),
     Subsequent(SubsequentRequest),
     BEvent(ChildEvent),
     AEvent(ParentEvent),
+    DialogFlowEvent(BotEvent<DialogFlowEvent>),
+    DialogFlowResult(DialogFlowResult),
+    LexEvent(BotEvent<LexEvent>),
+    LexResult(LexResult),
+    RasaEvent(BotEvent<RasaEvent>),
+    RasaResult(RasaResult),
+    ConferenceStatus(ConferenceStatus),
+    QueueWait(QueueWaitRequest),
+    Dequeue(DequeueOutcome),
+    Transcription(TranscriptionHook),
+    Message(InboundMessage),
+    MessageStatus(MessageStatusCallback),
+    ConferenceWait(ConferenceWaitRequest),
+    Amd(AmdEvent),
 }
 
+impl Request {
+    /// Decodes a hook body as the payload that `kind` posts. This is the
+    /// supported way to read a hook: the untagged `Deserialize` guesses from
+    /// the shape, and full jambonz payloads match `Initial` or `Subsequent`
+    /// before the verb specific variants.
+    pub fn decode(kind: &HookKind, data: &Value) -> Result<Request, DecodeError> {
+        match kind {
+            HookKind::Call => decode(kind, data, Request::Initial),
+            HookKind::CallStatus if data.get("parent_call_sid").is_some() => {
+                decode(kind, data, Request::BEvent)
+            }
+            HookKind::CallStatus => decode(kind, data, Request::AEvent),
+            HookKind::SipRequestWithinDialog => decode(kind, data, Request::SipRequestWithinDialog),
+            HookKind::Messaging => decode(kind, data, Request::Message),
+            HookKind::Other => decode(kind, data, Request::Subsequent),
+            HookKind::Verb { verb, hook } => match (verb.as_str(), hook.as_str()) {
+                ("dial", "actionHook") => decode(kind, data, Request::Dial),
+                ("amd", "actionHook") => decode(kind, data, Request::Amd),
+                ("enqueue", "actionHook") => decode(kind, data, Request::Queue),
+                ("enqueue", "waitHook") => decode(kind, data, Request::QueueWait),
+                ("dequeue", "actionHook") => decode(kind, data, Request::Dequeue),
+                ("conference", "statusHook") => decode(kind, data, Request::ConferenceStatus),
+                ("conference", "enterHook" | "waitHook") => {
+                    decode(kind, data, Request::ConferenceWait)
+                }
+                ("dialogflow", "eventHook") => decode(kind, data, Request::DialogFlowEvent),
+                ("dialogflow", "actionHook") => decode(kind, data, Request::DialogFlowResult),
+                ("lex", "eventHook") => decode(kind, data, Request::LexEvent),
+                ("lex", "actionHook") => decode(kind, data, Request::LexResult),
+                ("rasa", "eventHook") => decode(kind, data, Request::RasaEvent),
+                ("rasa", "actionHook") => decode(kind, data, Request::RasaResult),
+                (_, "transcriptionHook") => decode(kind, data, Request::Transcription),
+                (_, "sipRequestWithinDialogHook") => {
+                    decode(kind, data, Request::SipRequestWithinDialog)
+                }
+                ("message", "actionHook") => decode(kind, data, Request::MessageStatus),
+                _ => decode(kind, data, Request::Subsequent),
+            },
+        }
+    }
+}
+
+fn decode<T: DeserializeOwned>(
+    kind: &HookKind,
+    data: &Value,
+    variant: fn(T) -> Request,
+) -> Result<Request, DecodeError> {
+    serde_path_to_error::deserialize(data)
+        .map(variant)
+        .map_err(|e| DecodeError::new(kind, std::any::type_name::<T>(), e))
+}
+
+/// The hook a payload was posted to, which decides how `Request::decode` reads it.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum HookKind {
+    /// The application `call_hook`, posted when a call arrives.
+    Call,
+    /// The application `call_status_hook`, for both the parent and child legs.
+    CallStatus,
+    SipRequestWithinDialog,
+    /// The application messaging hook, posted when an SMS arrives.
+    Messaging,
+    /// A hook set on a verb, named by `Verb::name` (or by the nested object
+    /// that owns it, e.g. `amd`) and the camelCase hook property.
+    Verb { verb: String, hook: String },
+    /// A hook that cannot be traced to a verb, decoded as a `SubsequentRequest`.
+    #[default]
+    Other,
+}
+
+impl HookKind {
+    pub fn verb(verb: &str, hook: &str) -> HookKind {
+        HookKind::Verb {
+            verb: verb.to_string(),
+            hook: hook.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookKind::Call => write!(f, "callHook"),
+            HookKind::CallStatus => write!(f, "callStatusHook"),
+            HookKind::SipRequestWithinDialog => write!(f, "sipRequestWithinDialogHook"),
+            HookKind::Messaging => write!(f, "messagingHook"),
+            HookKind::Verb { verb, hook } => write!(f, "{} {}", verb, hook),
+            HookKind::Other => write!(f, "hook"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DecodeError {
+    pub hook: HookKind,
+    /// The payload type the hook was decoded as, e.g. "SubsequentDialRequest".
+    pub expected: &'static str,
+    /// Dotted path to the field that failed, empty for the top level object.
+    pub path: String,
+    /// The required field that was not sent, if that is why decoding failed.
+    pub missing_field: Option<String>,
+    pub source: serde_json::Error,
+}
+
+impl DecodeError {
+    fn new(
+        hook: &HookKind,
+        type_name: &'static str,
+        error: serde_path_to_error::Error<serde_json::Error>,
+    ) -> DecodeError {
+        let path = match error.path().to_string() {
+            path if path == "." => String::new(),
+            path => path,
+        };
+        let source = error.into_inner();
+        let missing_field = source
+            .to_string()
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split('`').next())
+            .map(|field| match path.as_str() {
+                "" => field.to_string(),
+                path => format!("{}.{}", path, field),
+            });
+        DecodeError {
+            hook: hook.clone(),
+            expected: type_name.rsplit("::").next().unwrap_or(type_name),
+            path,
+            missing_field,
+            source,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} payload is not a {}: ", self.hook, self.expected)?;
+        match (&self.missing_field, self.path.as_str()) {
+            (Some(field), _) => write!(f, "missing field `{}`", field),
+            (None, "") => write!(f, "{}", self.source),
+            (None, path) => write!(f, "{} at `{}`", self.source, path),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// A `call_status_hook` payload for the parent leg. jambonz leaves out the
+/// media server and SBC details until the call has reached one, e.g. on an
+/// `early-media` status.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ParentEvent {
     pub call_sid: String,
@@ -29,20 +203,24 @@ pub struct ParentEvent {
     pub from: String,
     pub to: String,
     pub call_id: String,
-    pub sbc_callid: String,
+    pub sbc_callid: Option<String>,
     pub sip_status: SIPStatus,
     pub sip_reason: String,
     pub call_status: String,
     pub account_sid: String,
     pub trace_id: String,
     pub application_sid: String,
-    pub fs_sip_address: String,
-    pub fs_public_ip: String,
-    pub api_base_url: String,
-    pub originating_sip_ip: String,
-    pub originating_sip_trunk_name: String,
+    pub fs_sip_address: Option<String>,
+    pub fs_public_ip: Option<String>,
+    pub api_base_url: Option<String>,
+    pub originating_sip_ip: Option<String>,
+    pub originating_sip_trunk_name: Option<String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
 }
 
+/// A `call_status_hook` payload for a child leg, e.g. one placed by `dial`.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ChildEvent {
     pub call_sid: String,
@@ -50,17 +228,20 @@ pub struct ChildEvent {
     pub from: String,
     pub to: String,
     pub call_id: String,
-    pub sbc_callid: String,
+    pub sbc_callid: Option<String>,
     pub sip_status: SIPStatus,
     pub sip_reason: String,
     pub call_status: String,
     pub account_sid: String,
     pub trace_id: String,
     pub application_sid: String,
-    pub fs_sip_address: String,
-    pub fs_public_ip: String,
+    pub fs_sip_address: Option<String>,
+    pub fs_public_ip: Option<String>,
     pub parent_call_sid: String,
-    pub api_base_url: String,
+    pub api_base_url: Option<String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -82,13 +263,34 @@ pub struct InitialRequest {
     pub local_sip_address: String,
     pub originating_sip_ip: Option<String>,
     pub originating_sip_trunk_name: Option<String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
 }
 
 impl InitialRequest {
-    pub fn get_contact_ip(&self) -> String {
-        self.sip.get_contact_ip()
+    /// The ip address in the `Contact` header.
+    pub fn try_get_contact_ip(&self) -> Result<IpAddr, Error> {
+        self.sip.try_get_contact_ip()
+    }
+
+    pub fn try_get_tenant_type(&self, proxies: Vec<&str>) -> Result<TenantType, Error> {
+        Ok(if self.sip.try_has_proxy(proxies)? {
+            TenantType::PROXY
+        } else if self.sip.has_teams() {
+            TenantType::TEAMS
+        } else if self.sip.has_user() {
+            TenantType::USER
+        } else {
+            TenantType::TRUNK
+        })
+        //todo impl APPLICATION
     }
 
+    /// Treats a call as not from a proxy if the proxies or `X-Forwarded-For`
+    /// cannot be parsed, use `try_get_tenant_type` to handle the error instead.
+    #[deprecated(note = "use try_get_tenant_type")]
+    #[allow(deprecated)]
     pub fn get_tenant_type(&self, proxies: Vec<&str>) -> TenantType {
         if self.sip.has_proxy(proxies) {
             TenantType::PROXY
@@ -99,7 +301,83 @@ impl InitialRequest {
         } else {
             TenantType::TRUNK
         }
-        //todo impl APPLICATION
+    }
+}
+
+/// Sent to `Config::sip_request_within_dialog_hook` when the far end
+/// sends an INFO, NOTIFY or MESSAGE on an established call.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SipRequestWithinDialog {
+    pub call_sid: String,
+    pub call_id: String,
+    pub direction: Direction,
+    pub from: String,
+    pub to: String,
+    pub account_sid: String,
+    pub application_sid: String,
+    pub sip_method: SipMethod,
+    pub sip_body: Option<String>,
+    #[serde(default)]
+    pub sip_headers: HashMap<String, String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+impl SipRequestWithinDialog {
+    pub fn content_type(&self) -> Option<&str> {
+        self.sip_headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn respond(&self, status: SIPStatus) -> SipRequestWithinDialogResponse {
+        SipRequestWithinDialogResponse::new(status)
+    }
+}
+
+/// The SIP response jambonz sends back for a request within the dialog.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SipRequestWithinDialogResponse {
+    pub status: SIPStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+}
+
+impl SipRequestWithinDialogResponse {
+    pub fn new(status: SIPStatus) -> SipRequestWithinDialogResponse {
+        SipRequestWithinDialogResponse {
+            status,
+            reason: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    pub fn ok() -> SipRequestWithinDialogResponse {
+        SipRequestWithinDialogResponse::new(SIPStatus::Ok)
+    }
+
+    pub fn reason(&mut self, reason: Option<String>) -> &mut SipRequestWithinDialogResponse {
+        self.reason = reason;
+        self
+    }
+
+    pub fn header(&mut self, key: &str, value: &str) -> &mut SipRequestWithinDialogResponse {
+        self.headers.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn as_ack(&self, msg_id: &str) -> SipRequestAck {
+        SipRequestAck {
+            msgid: msg_id.to_string(),
+            data: self.clone(),
+        }
     }
 }
 
@@ -121,10 +399,14 @@ pub struct SubsequentRequest {
     pub duration: Option<u16>,
     pub digits: Option<String>,
     pub speech: Option<Speech>,
-    #[serde(alias = "customerData")]
+    pub reason: Option<String>,
+    #[serde(rename = "customerData")]
     #[serde(alias = "customerdata")]
     #[serde(alias = "customer_data")]
     pub customer_data: CustomerData,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -140,6 +422,9 @@ pub struct CustomerData {
     pub customer_id: Option<String>,
     pub teams_id: Option<String>,
     pub client_id: Option<String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -160,13 +445,492 @@ pub struct SubsequentDialRequest {
     pub duration: Option<u16>,
     pub digits: Option<String>,
     pub speech: Option<Speech>,
-    #[serde(alias = "customerData")]
+    #[serde(rename = "customerData")]
     #[serde(alias = "customerdata")]
     #[serde(alias = "customer_data")]
     pub customer_data: HashMap<String, String>,
     pub dial_call_sid: String,
     pub dial_call_status: String,
     pub dial_sip_status: SIPStatus,
+    pub dial_sbc_callid: Option<String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// The outcome posted to a dial `actionHook` once the outbound leg ends.
+/// Call details sent alongside the outcome are ignored.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialOutcome {
+    pub call_sid: String,
+    pub dial_call_sid: Option<String>,
+    pub dial_call_status: DialCallStatus,
+    pub dial_sip_status: Option<SIPStatus>,
+    pub dial_sbc_callid: Option<String>,
+    /// Seconds the bridged call lasted, zero if it was never answered.
+    #[serde(default)]
+    pub duration: u32,
+    pub hangup_by: Option<DialLeg>,
+}
+
+impl DialOutcome {
+    pub fn answered(&self) -> bool {
+        matches!(self.dial_call_status, DialCallStatus::Completed)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.duration as u64)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum DialCallStatus {
+    Completed,
+    Failed,
+    Busy,
+    NoAnswer,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum DialLeg {
+    Caller,
+    Callee,
+}
+
+/// An answering machine detection event posted to `Amd::action_hook`.
+/// Call details sent alongside the event are ignored.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum AmdEvent {
+    #[serde(rename = "amd_human_detected")]
+    HumanDetected {
+        reason: Option<String>,
+        greeting: Option<String>,
+        language: Option<String>,
+    },
+    #[serde(rename = "amd_machine_detected")]
+    MachineDetected {
+        reason: Option<String>,
+        greeting: Option<String>,
+        hint: Option<String>,
+        language: Option<String>,
+    },
+    #[serde(rename = "amd_no_speech_detected")]
+    NoSpeechDetected,
+    #[serde(rename = "amd_decision_timeout")]
+    DecisionTimeout,
+    #[serde(rename = "amd_machine_stopped_speaking")]
+    MachineStoppedSpeaking,
+    #[serde(rename = "amd_tone_detected")]
+    ToneDetected,
+    #[serde(rename = "amd_error")]
+    Error { error: Option<String> },
+}
+
+impl AmdEvent {
+    pub fn is_machine(&self) -> bool {
+        matches!(
+            self,
+            AmdEvent::MachineDetected { .. }
+                | AmdEvent::MachineStoppedSpeaking
+                | AmdEvent::ToneDetected
+        )
+    }
+}
+
+/// Posted to `Conference::status_hook` for each of the configured `status_events`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConferenceStatus {
+    pub conference_sid: String,
+    pub friendly_name: String,
+    pub event: ConferenceStatusEvent,
+    pub time: String,
+    /// The number of participants after this event.
+    pub members: u16,
+    /// Seconds the conference lasted, sent with the `end` event.
+    pub duration: Option<u32>,
+    /// The participant that joined, left or is talking.
+    pub call_sid: Option<String>,
+    #[serde(default)]
+    #[serde(deserialize_with = "string_or_number")]
+    pub member_id: Option<String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// Posted to `Conference::enter_hook` and `Conference::wait_hook`,
+/// which return verbs to play while the caller enters or waits.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConferenceWaitRequest {
+    pub call_sid: String,
+    pub conference_sid: Option<String>,
+    pub friendly_name: Option<String>,
+    pub members: Option<u16>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(value)) => Some(value),
+        Some(Value::Number(value)) => Some(value.to_string()),
+        _ => None,
+    })
+}
+
+/// How a dialogflow, lex or rasa session ended, as posted to its `actionHook`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum BotCompletion {
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "caller hungup")]
+    CallerHungup,
+    #[serde(rename = "timeout")]
+    Timeout,
+    #[serde(rename = "error")]
+    Error,
+    #[serde(other)]
+    Unknown,
+}
+
+/// An error raised by the bot vendor during the session.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BotError {
+    #[serde(alias = "message")]
+    pub error: Option<String>,
+
+    #[serde(flatten)]
+    pub details: HashMap<String, Value>,
+}
+
+/// A dialogflow, lex or rasa event as posted to the verb's `eventHook`,
+/// with the call details jambonz sends alongside it kept in `metadata`.
+#[derive(Serialize, Clone)]
+pub struct BotEvent<E> {
+    #[serde(flatten)]
+    pub event: E,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// The event is read from the whole body, and whatever it does not carry
+/// is kept as `metadata`.
+impl<'de, E> Deserialize<'de> for BotEvent<E>
+where
+    E: Serialize + DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<BotEvent<E>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let mut metadata = serde_json::Map::deserialize(deserializer)?;
+        let event: E =
+            serde_json::from_value(Value::Object(metadata.clone())).map_err(D::Error::custom)?;
+        if let Ok(Value::Object(carried)) = serde_json::to_value(&event) {
+            metadata.retain(|key, _| !carried.contains_key(key));
+        }
+        Ok(BotEvent {
+            event,
+            metadata: metadata.into_iter().collect(),
+        })
+    }
+}
+
+/// An event posted to `DialogFlow::event_hook`. Works with both ES and CX agents.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "event", content = "data")]
+pub enum DialogFlowEvent {
+    #[serde(rename = "intent")]
+    Intent(DialogFlowIntent),
+    #[serde(rename = "transcription")]
+    Transcription(DialogFlowTranscription),
+    #[serde(rename = "audio")]
+    #[serde(alias = "audio-response")]
+    AudioResponse(DialogFlowAudioResponse),
+    #[serde(rename = "end-interaction")]
+    EndInteraction,
+    #[serde(rename = "no-input")]
+    NoInput,
+    #[serde(rename = "error")]
+    Error(BotError),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialogFlowIntent {
+    #[serde(alias = "responseId")]
+    pub response_id: Option<String>,
+
+    #[serde(alias = "queryResult")]
+    pub query_result: DialogFlowQueryResult,
+}
+
+impl DialogFlowIntent {
+    /// What the caller said (ES `query_text`, CX `text`).
+    pub fn query_text(&self) -> Option<&str> {
+        self.query_result
+            .query_text
+            .as_deref()
+            .or(self.query_result.text.as_deref())
+    }
+
+    /// The display name of the matched intent (ES `intent`, CX `match.intent`).
+    pub fn display_name(&self) -> Option<&str> {
+        self.query_result.matched_intent()?.display_name.as_deref()
+    }
+
+    pub fn confidence(&self) -> Option<f32> {
+        self.query_result.intent_detection_confidence.or_else(|| {
+            self.query_result
+                .intent_match
+                .as_ref()
+                .and_then(|intent_match| intent_match.confidence)
+        })
+    }
+
+    pub fn is_end_interaction(&self) -> bool {
+        self.query_result
+            .matched_intent()
+            .map(|intent| intent.end_interaction)
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialogFlowQueryResult {
+    #[serde(alias = "queryText")]
+    pub query_text: Option<String>,
+
+    /// The CX equivalent of `query_text`.
+    pub text: Option<String>,
+
+    #[serde(alias = "languageCode")]
+    pub language_code: Option<String>,
+
+    pub action: Option<String>,
+
+    #[serde(default)]
+    pub parameters: Value,
+
+    #[serde(alias = "fulfillmentText")]
+    pub fulfillment_text: Option<String>,
+
+    pub intent: Option<DialogFlowIntentInfo>,
+
+    #[serde(alias = "intentDetectionConfidence")]
+    pub intent_detection_confidence: Option<f32>,
+
+    /// The CX equivalent of `intent` and `intent_detection_confidence`.
+    #[serde(rename = "match")]
+    pub intent_match: Option<DialogFlowMatch>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl DialogFlowQueryResult {
+    pub fn matched_intent(&self) -> Option<&DialogFlowIntentInfo> {
+        self.intent.as_ref().or_else(|| {
+            self.intent_match
+                .as_ref()
+                .and_then(|intent_match| intent_match.intent.as_ref())
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialogFlowIntentInfo {
+    pub name: Option<String>,
+
+    #[serde(alias = "displayName")]
+    pub display_name: Option<String>,
+
+    #[serde(alias = "endInteraction")]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub end_interaction: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialogFlowMatch {
+    pub intent: Option<DialogFlowIntentInfo>,
+    pub confidence: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialogFlowTranscription {
+    #[serde(alias = "recognitionResult")]
+    pub recognition_result: DialogFlowRecognitionResult,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialogFlowRecognitionResult {
+    pub transcript: String,
+
+    #[serde(alias = "isFinal")]
+    #[serde(default)]
+    pub is_final: bool,
+
+    pub confidence: Option<f32>,
+
+    #[serde(alias = "languageCode")]
+    pub language_code: Option<String>,
+}
+
+/// Audio returned by dialogflow, saved by jambonz before it is played.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialogFlowAudioResponse {
+    pub path: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Posted to `DialogFlow::action_hook` when the session ends.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DialogFlowResult {
+    #[serde(rename = "dialogflowResult")]
+    pub result: BotCompletion,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// An event posted to `Lex::event_hook` (Lex V2).
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "event", content = "data")]
+pub enum LexEvent {
+    #[serde(rename = "intent")]
+    Intent(LexIntent),
+    #[serde(rename = "transcription")]
+    Transcription(LexTranscription),
+    #[serde(rename = "response-text")]
+    ResponseText(LexResponseText),
+    #[serde(rename = "response-audio")]
+    #[serde(alias = "audio")]
+    AudioResponse(LexAudioResponse),
+    #[serde(rename = "end-interaction")]
+    EndInteraction,
+    #[serde(rename = "error")]
+    Error(BotError),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LexIntent {
+    pub session_state: LexSessionState,
+
+    #[serde(default)]
+    pub interpretations: Vec<Value>,
+}
+
+impl LexIntent {
+    pub fn name(&self) -> Option<&str> {
+        self.session_state.intent.as_ref().map(|intent| intent.name.as_str())
+    }
+
+    /// True once lex has closed the dialog for this intent.
+    pub fn is_end_interaction(&self) -> bool {
+        self.session_state
+            .dialog_action
+            .as_ref()
+            .and_then(|action| action.get("type"))
+            .and_then(Value::as_str)
+            == Some("Close")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LexSessionState {
+    pub intent: Option<LexIntentInfo>,
+
+    pub dialog_action: Option<Value>,
+
+    #[serde(default)]
+    pub session_attributes: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LexIntentInfo {
+    pub name: String,
+
+    pub state: Option<String>,
+
+    pub confirmation_state: Option<String>,
+
+    #[serde(default)]
+    pub slots: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LexTranscription {
+    pub transcript: String,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LexResponseText {
+    #[serde(default)]
+    pub messages: Vec<Value>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LexAudioResponse {
+    pub path: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Posted to the lex `actionHook` when the session ends.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LexResult {
+    #[serde(rename = "lexResult")]
+    pub result: BotCompletion,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// An event posted to `Rasa::event_hook`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "event")]
+pub enum RasaEvent {
+    /// What the caller said, as sent to rasa.
+    #[serde(rename = "userMessage")]
+    UserMessage { message: String },
+    /// The rasa response, as played to the caller.
+    #[serde(rename = "botMessage")]
+    BotMessage { message: Value },
+    #[serde(rename = "error")]
+    Error(BotError),
+}
+
+/// Posted to `Rasa::action_hook` when the session ends.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RasaResult {
+    #[serde(rename = "rasaResult")]
+    pub result: BotCompletion,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -189,29 +953,212 @@ pub struct SubsequentQueueRequest {
     pub duration: Option<u16>,
     pub digits: Option<String>,
     pub speech: Option<Speech>,
-    #[serde(alias = "customerData")]
+    #[serde(rename = "customerData")]
     #[serde(alias = "customerdata")]
     #[serde(alias = "customer_data")]
     pub customer_data: HashMap<String, String>,
     pub queue_sid: String,
-    pub queue_time: u16,
-    pub queue_position: u16,
-    pub queue_size: u16,
+    pub queue_time: u32,
+    pub queue_position: u32,
+    pub queue_size: u32,
     pub queue_result: QueueResult,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum QueueResult {
     Hangup,
+    /// The call left the queue because a `leave` verb was executed.
     Leave,
     Bridged,
+    Timeout,
     Error,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Posted to `Enqueue::action_hook` when the call leaves the queue.
+/// Call details sent alongside the outcome are ignored.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EnqueueOutcome {
+    pub call_sid: String,
+    pub queue_sid: String,
+    pub queue_result: QueueResult,
+    /// Seconds the call spent in the queue.
+    #[serde(default)]
+    pub queue_time: u32,
+}
+
+impl EnqueueOutcome {
+    pub fn time_in_queue(&self) -> Duration {
+        Duration::from_secs(self.queue_time as u64)
+    }
+
+    pub fn left(&self) -> bool {
+        self.queue_result == QueueResult::Leave
+    }
+}
+
+/// Posted to `Enqueue::wait_hook` while the call is waiting,
+/// which returns the verbs (e.g. hold music) to play next.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QueueWaitRequest {
+    pub call_sid: String,
+    pub queue_sid: String,
+    /// Zero based position of this call in the queue.
+    pub queue_position: u32,
+    pub queue_size: u32,
+    /// Seconds the call has been waiting so far.
+    #[serde(default)]
+    pub queue_time: u32,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+impl QueueWaitRequest {
+    pub fn time_waited(&self) -> Duration {
+        Duration::from_secs(self.queue_time as u64)
+    }
+}
+
+/// Posted to `Dequeue::action_hook` once the dequeue attempt completes.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DequeueOutcome {
+    pub call_sid: String,
+    pub dequeue_result: DequeueResult,
+    pub queue_sid: Option<String>,
+    /// The call that was taken off the queue, if any.
+    pub dequeued_call_sid: Option<String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum DequeueResult {
+    Bridged,
+    Timeout,
+    Hangup,
+    Error,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The outcome posted to a gather `actionHook`, keyed on `reason`.
+/// Call details sent alongside a known result are ignored.
+#[derive(Serialize, Clone)]
+#[serde(tag = "reason")]
+pub enum GatherResult {
+    #[serde(rename = "dtmfDetected")]
+    Digits { digits: String },
+    #[serde(rename = "speechDetected")]
+    Speech { speech: SpeechResult },
+    #[serde(rename = "timeout")]
+    Timeout,
+    #[serde(rename = "stt-error")]
+    SttError { details: Option<Value> },
+    /// A reason not modelled above, e.g. `stt-low-confidence`, with the rest
+    /// of the body as sent.
+    #[serde(untagged)]
+    Other {
+        reason: String,
+        #[serde(flatten)]
+        data: serde_json::Map<String, Value>,
+    },
+}
+
+/// Known reasons decode strictly; a bad body for one of them is an error
+/// rather than an `Other`.
+impl<'de> Deserialize<'de> for GatherResult {
+    fn deserialize<D>(deserializer: D) -> Result<GatherResult, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let mut data = serde_json::Map::deserialize(deserializer)?;
+        let reason = match data.remove("reason") {
+            Some(Value::String(reason)) => reason,
+            Some(other) => return Err(D::Error::custom(format!("invalid reason {}", other))),
+            None => return Err(D::Error::missing_field("reason")),
+        };
+        let mut field = |name: &'static str| match data.remove(name) {
+            Some(value) => Ok(value),
+            None => Err(D::Error::missing_field(name)),
+        };
+        Ok(match reason.as_str() {
+            "dtmfDetected" => GatherResult::Digits {
+                digits: serde_json::from_value(field("digits")?).map_err(D::Error::custom)?,
+            },
+            "speechDetected" => GatherResult::Speech {
+                speech: serde_json::from_value(field("speech")?).map_err(D::Error::custom)?,
+            },
+            "timeout" => GatherResult::Timeout,
+            "stt-error" | "error" => GatherResult::SttError {
+                details: field("details").ok(),
+            },
+            _ => GatherResult::Other { reason, data },
+        })
+    }
+}
+
+impl GatherResult {
+    pub fn digits(&self) -> Option<&str> {
+        match self {
+            GatherResult::Digits { digits } => Some(digits),
+            _ => None,
+        }
+    }
+
+    /// The transcript of the most likely alternative.
+    pub fn transcript(&self) -> Option<&str> {
+        match self {
+            GatherResult::Speech { speech } => speech.transcript(),
+            _ => None,
+        }
+    }
+}
+
+/// A recognition result in jambonz's normalized form.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SpeechResult {
+    #[serde(default)]
+    pub is_final: bool,
+    pub language_code: Option<String>,
+    pub channel_tag: Option<u8>,
+    #[serde(default)]
+    pub alternatives: Vec<SpeechAlternative>,
+    /// The vendor name and raw vendor event, when jambonz includes them.
+    pub vendor: Option<Value>,
+}
+
+impl SpeechResult {
+    pub fn transcript(&self) -> Option<&str> {
+        self.alternatives.first().map(|alt| alt.transcript.as_str())
+    }
+
+    pub fn confidence(&self) -> Option<f32> {
+        self.alternatives.first().and_then(|alt| alt.confidence)
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.language_code.as_deref()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SpeechAlternative {
+    pub transcript: String,
+    pub confidence: Option<f32>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Speech {
     pub stability: Option<u8>,
+    #[serde(alias = "is_final")]
+    #[serde(default)]
     pub final_result: bool,
     #[serde(default)]
     pub alternatives: Vec<Alternative>,
@@ -223,6 +1170,190 @@ pub struct Alternative {
     pub transcript: String,
 }
 
+/// Posted to the application's messaging hook when an SMS or MMS arrives.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundMessage {
+    #[serde(alias = "message_sid")]
+    pub message_sid: String,
+    #[serde(alias = "account_sid")]
+    pub account_sid: Option<String>,
+    #[serde(alias = "application_sid")]
+    pub application_sid: Option<String>,
+    /// The carrier (or messaging provider) the message arrived on.
+    #[serde(alias = "provider")]
+    pub carrier: Option<String>,
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub cc: Vec<String>,
+    #[serde(default)]
+    pub text: String,
+    /// Urls of any attached media.
+    #[serde(default)]
+    pub media: Vec<String>,
+}
+
+impl InboundMessage {
+    /// A `message` verb that answers the sender from the number they messaged.
+    pub fn reply(&self, text: String) -> Message {
+        let mut message = Message::new(self.to.clone(), self.from.clone(), text);
+        message.with_carrier(self.carrier.clone());
+        message
+    }
+}
+
+/// Posted to the `actionHook` of a `message` verb once the carrier has been tried.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MessageStatusCallback {
+    pub message_sid: String,
+    pub message_status: MessageStatus,
+    pub carrier: Option<String>,
+    /// The id the carrier assigned to the message, if it was accepted.
+    pub carrier_message_id: Option<String>,
+    pub message_failure_reason: Option<String>,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum MessageStatus {
+    #[serde(rename = "success")]
+    Success,
+    #[serde(rename = "failure")]
+    Failure,
+    #[serde(rename = "no carriers")]
+    NoCarriers,
+    #[serde(rename = "smpp configuration error")]
+    SmppConfigurationError,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Posted to the `transcriptionHook` of `Transcribe`, `TranscribeDial`
+/// and `TranscribeConfig` for each (interim or final) transcript.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TranscriptionHook {
+    pub call_sid: String,
+    pub speech: TranscriptionEvent,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+/// A transcript in jambonz's normalized form. The untouched vendor event
+/// is kept in `vendor` for fields that jambonz does not normalize.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TranscriptionEvent {
+    #[serde(default)]
+    pub is_final: bool,
+    pub language_code: Option<String>,
+    /// 1 for the caller and 2 for the called party when transcribing both legs.
+    pub channel_tag: Option<u8>,
+    #[serde(alias = "speaker_label")]
+    #[serde(alias = "speakerLabel")]
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub speaker: Option<String>,
+    #[serde(default)]
+    pub alternatives: Vec<TranscriptionAlternative>,
+    pub vendor: Option<TranscriptionVendor>,
+}
+
+impl TranscriptionEvent {
+    pub fn transcript(&self) -> Option<&str> {
+        self.alternatives.first().map(|alt| alt.transcript.as_str())
+    }
+
+    pub fn confidence(&self) -> Option<f32> {
+        self.alternatives.first().and_then(|alt| alt.confidence)
+    }
+
+    pub fn words(&self) -> &[WordTiming] {
+        self.alternatives
+            .first()
+            .map(|alt| alt.words.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The event speaker label, or the label of the first diarized word.
+    pub fn speaker(&self) -> Option<&str> {
+        self.speaker
+            .as_deref()
+            .or_else(|| self.words().iter().find_map(|word| word.speaker.as_deref()))
+    }
+
+    /// Splits the best alternative into consecutive runs of words by speaker.
+    /// Words without a speaker label inherit the event speaker.
+    pub fn speaker_turns(&self) -> Vec<SpeakerTurn> {
+        let mut turns: Vec<SpeakerTurn> = vec![];
+        for word in self.words() {
+            let speaker = word.speaker.clone().or_else(|| self.speaker.clone());
+            match turns.last_mut() {
+                Some(turn) if turn.speaker == speaker => {
+                    turn.text.push(' ');
+                    turn.text.push_str(&word.word);
+                    turn.end_time = word.end_time.or(turn.end_time);
+                }
+                _ => turns.push(SpeakerTurn {
+                    speaker,
+                    channel_tag: self.channel_tag,
+                    text: word.word.clone(),
+                    start_time: word.start_time,
+                    end_time: word.end_time,
+                }),
+            }
+        }
+        turns
+    }
+
+    /// The raw vendor event, for vendors whose results are passed through.
+    pub fn raw(&self) -> Option<&Value> {
+        self.vendor.as_ref().and_then(|vendor| vendor.evt.as_ref())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TranscriptionAlternative {
+    pub transcript: String,
+    pub confidence: Option<f32>,
+    #[serde(default)]
+    pub words: Vec<WordTiming>,
+}
+
+/// Timings are in seconds from the start of the transcription.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WordTiming {
+    #[serde(alias = "punctuated_word")]
+    pub word: String,
+    #[serde(alias = "start")]
+    #[serde(alias = "startTime")]
+    pub start_time: Option<f64>,
+    #[serde(alias = "end")]
+    #[serde(alias = "endTime")]
+    pub end_time: Option<f64>,
+    pub confidence: Option<f32>,
+    #[serde(alias = "speaker_label")]
+    #[serde(alias = "speakerTag")]
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub speaker: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TranscriptionVendor {
+    pub name: String,
+    pub evt: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SpeakerTurn {
+    pub speaker: Option<String>,
+    pub channel_tag: Option<u8>,
+    pub text: String,
+    pub start_time: Option<f64>,
+    pub end_time: Option<f64>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SipPayload {
     pub headers: SipPayloadHeaders,
@@ -235,8 +1366,8 @@ pub struct SipPayload {
 }
 
 impl SipPayload {
-    pub  fn get_contact_ip(&self) -> String {
-        self.headers.get_contact_ip()
+    pub fn try_get_contact_ip(&self) -> Result<IpAddr, Error> {
+        self.headers.try_get_contact_ip()
     }
 
     pub  fn has_user(&self) -> bool {
@@ -247,15 +1378,35 @@ impl SipPayload {
         self.headers.x_ms_teams_tenant_fqdn.is_some()
     }
 
-    pub fn has_proxy(&self, proxies: Vec<&str>) -> bool {
+    /// Whether the client address in `X-Forwarded-For` is in any of the
+    /// `proxies` subnets, e.g. "10.0.0.0/8". The header is only read if
+    /// there are proxies to check it against. An ipv6 address is never in
+    /// an ipv4 subnet, and the other way round.
+    pub fn try_has_proxy(&self, proxies: Vec<&str>) -> Result<bool, Error> {
+        if proxies.is_empty() {
+            return Ok(false);
+        }
+        // Parsed first, so that `iface_in_subnet` can only fail on the subnet.
+        let address = self.headers.try_get_forwarded_for()?.to_string();
         let mut is_match = false;
         for x in proxies {
-            let res = iface_in_subnet(self.headers.x_forwarded_for.as_str(), &x).unwrap();
+            let res = iface_in_subnet(&address, x)
+                .map_err(|_| Error::InvalidSubnet(x.to_string()))?;
             if res {
                 is_match = true;
             }
         }
-        is_match
+        Ok(is_match)
+    }
+
+    /// Returns false if the proxies or `X-Forwarded-For` cannot be parsed,
+    /// use `try_has_proxy` to handle the error instead.
+    #[deprecated(note = "use try_has_proxy")]
+    pub fn has_proxy(&self, proxies: Vec<&str>) -> bool {
+        self.try_has_proxy(proxies).unwrap_or_else(|e| {
+            error!("{}", e);
+            false
+        })
     }
 }
 
@@ -302,19 +1453,577 @@ pub struct SipPayloadHeaders {
     pub x_ms_teams_fqdn: Option<String>,
 }
 
+/// The host of a `Contact` header, e.g. `"Bob" <sip:bob@10.0.0.1:5060;transport=udp>`,
+/// with ipv6 hosts in brackets.
+static CONTACT_HOST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:[^<]*<)?sips?:(?:[^@>]*@)?(?:\[([^\]]+)\]|([^:;>]+))").unwrap()
+});
+
 impl SipPayloadHeaders {
-    fn get_contact_ip(&self) -> String {
-        let re = Regex::new(r"<sip:(.*?):.*").unwrap();
-        if let Some(mat) = re.find(&self.contact) {
-            mat.as_str()
-                .replace("<sip:", "")
-                .replace(">", "")
-                .split(":")
-                .next()
-                .unwrap_or("1.1.1.1")
-                .into()
-        } else {
-            String::from("1.1.1.1")
+    pub fn try_get_contact_ip(&self) -> Result<IpAddr, Error> {
+        CONTACT_HOST
+            .captures(self.contact.trim())
+            .and_then(|captures| captures.get(1).or(captures.get(2)))
+            .and_then(|host| host.as_str().parse().ok())
+            .ok_or_else(|| Error::InvalidContact(self.contact.clone()))
+    }
+
+    /// The client address, the first entry of `X-Forwarded-For`. A port is
+    /// ignored, e.g. `10.0.0.1:5060` or `[2001:db8::1]:5060`.
+    pub fn try_get_forwarded_for(&self) -> Result<IpAddr, Error> {
+        let client = self.x_forwarded_for.split(',').next().unwrap_or_default().trim();
+        client
+            .parse()
+            .or_else(|_| client.parse::<SocketAddr>().map(|address| address.ip()))
+            .map_err(|_| Error::InvalidAddress(self.x_forwarded_for.clone()))
+    }
+}
+
+#[test]
+fn dial_outcome() {
+    let mut data: serde_json::Value =
+        serde_json::from_str(include_str!("../assets/subsequent-dial-request.json")).unwrap();
+    data["dial_sbc_callid"] = "7b1c2d3e@10.0.0.1".into();
+    data["duration"] = 754.into();
+    data["hangup_by"] = "callee".into();
+
+    let outcome: DialOutcome = serde_json::from_value(data).unwrap();
+    assert!(outcome.answered());
+    assert_eq!(outcome.dial_call_status, DialCallStatus::Completed);
+    assert!(matches!(outcome.dial_sip_status, Some(SIPStatus::Ok)));
+    assert_eq!(outcome.duration().as_secs(), 754);
+    assert_eq!(outcome.hangup_by, Some(DialLeg::Callee));
+
+    let busy: DialOutcome = serde_json::from_str(
+        r#"{"call_sid": "9f6a", "dial_call_status": "busy", "dial_sip_status": 486}"#,
+    )
+    .unwrap();
+    assert!(!busy.answered());
+    assert!(matches!(busy.dial_sip_status, Some(SIPStatus::BusyHere)));
+
+    let unknown: DialOutcome =
+        serde_json::from_str(r#"{"call_sid": "9f6a", "dial_call_status": "queued"}"#).unwrap();
+    assert_eq!(unknown.dial_call_status, DialCallStatus::Unknown);
+}
+
+#[test]
+fn amd_event() {
+    let machine: AmdEvent = serde_json::from_str(
+        r#"{
+            "type": "amd_machine_detected",
+            "reason": "long greeting",
+            "greeting": "Hi, you have reached the voicemail of",
+            "language": "en-GB",
+            "call_sid": "9f6a"
+        }"#,
+    )
+    .unwrap();
+    assert!(machine.is_machine());
+    match machine {
+        AmdEvent::MachineDetected { reason, .. } => {
+            assert_eq!(reason.as_deref(), Some("long greeting"))
+        }
+        _ => panic!("expected amd_machine_detected"),
+    }
+
+    let beep: AmdEvent = serde_json::from_str(r#"{"type": "amd_tone_detected"}"#).unwrap();
+    assert!(matches!(beep, AmdEvent::ToneDetected));
+
+    let error: AmdEvent =
+        serde_json::from_str(r#"{"type": "amd_error", "error": "recognizer failed"}"#).unwrap();
+    assert!(!error.is_machine());
+}
+
+#[test]
+fn bot_events() {
+    let intent: DialogFlowEvent = serde_json::from_str(
+        r#"{
+            "event": "intent",
+            "call_sid": "9f6a",
+            "data": {
+                "response_id": "3d2c",
+                "query_result": {
+                    "text": "I want to pay my bill",
+                    "language_code": "en",
+                    "parameters": { "account": "12345" },
+                    "match": {
+                        "intent": { "name": "projects/p/intents/1", "display_name": "billing" },
+                        "confidence": 0.87
+                    }
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+    match intent {
+        DialogFlowEvent::Intent(intent) => {
+            assert_eq!(intent.display_name(), Some("billing"));
+            assert_eq!(intent.confidence(), Some(0.87));
+            assert_eq!(intent.query_text(), Some("I want to pay my bill"));
+            assert!(!intent.is_end_interaction());
+        }
+        _ => panic!("expected intent"),
+    }
+
+    let end: DialogFlowEvent = serde_json::from_str(r#"{"event": "end-interaction"}"#).unwrap();
+    assert!(matches!(end, DialogFlowEvent::EndInteraction));
+
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/dialogflow-event.json"))
+            .unwrap();
+    match Request::decode(&HookKind::verb("dialogflow", "eventHook"), &data).unwrap() {
+        Request::DialogFlowEvent(BotEvent {
+            event: DialogFlowEvent::Intent(intent),
+            metadata,
+        }) => {
+            assert_eq!(intent.display_name(), Some("billing"));
+            assert_eq!(intent.query_text(), Some("I want to pay my bill"));
+            assert_eq!(metadata["direction"], "inbound");
+            assert!(!metadata.contains_key("event"));
+        }
+        _ => panic!("expected dialogflow intent"),
+    }
+
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/dialogflow-action.json"))
+            .unwrap();
+    match Request::decode(&HookKind::verb("dialogflow", "actionHook"), &data).unwrap() {
+        Request::DialogFlowResult(result) => assert_eq!(result.result, BotCompletion::CallerHungup),
+        _ => panic!("expected dialogflow result"),
+    }
+
+    let lex: LexEvent = serde_json::from_str(
+        r#"{
+            "event": "intent",
+            "data": {
+                "sessionState": {
+                    "intent": { "name": "BookHotel", "state": "Fulfilled", "slots": {} },
+                    "dialogAction": { "type": "Close" }
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+    match lex {
+        LexEvent::Intent(intent) => {
+            assert_eq!(intent.name(), Some("BookHotel"));
+            assert!(intent.is_end_interaction());
+        }
+        _ => panic!("expected lex intent"),
+    }
+
+    let rasa: RasaEvent =
+        serde_json::from_str(r#"{"event": "userMessage", "message": "opening hours"}"#).unwrap();
+    assert!(matches!(rasa, RasaEvent::UserMessage { message } if message == "opening hours"));
+
+    // The same `event` tags are sent by dialogflow, lex and rasa, so only the
+    // hook kind tells them apart.
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/lex-event.json")).unwrap();
+    assert!(matches!(
+        Request::decode(&HookKind::verb("lex", "eventHook"), &data).unwrap(),
+        Request::LexEvent(BotEvent {
+            event: LexEvent::EndInteraction,
+            ..
+        })
+    ));
+    assert!(matches!(
+        Request::decode(&HookKind::verb("dialogflow", "eventHook"), &data).unwrap(),
+        Request::DialogFlowEvent(BotEvent {
+            event: DialogFlowEvent::EndInteraction,
+            ..
+        })
+    ));
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/lex-action.json")).unwrap();
+    match Request::decode(&HookKind::verb("lex", "actionHook"), &data).unwrap() {
+        Request::LexResult(result) => assert_eq!(result.result, BotCompletion::Completed),
+        _ => panic!("expected lex result"),
+    }
+
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/rasa-event.json")).unwrap();
+    match Request::decode(&HookKind::verb("rasa", "eventHook"), &data).unwrap() {
+        Request::RasaEvent(BotEvent {
+            event: RasaEvent::UserMessage { message },
+            ..
+        }) => {
+            assert_eq!(message, "opening hours")
+        }
+        _ => panic!("expected rasa user message"),
+    }
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/rasa-action.json")).unwrap();
+    match Request::decode(&HookKind::verb("rasa", "actionHook"), &data).unwrap() {
+        Request::RasaResult(result) => assert_eq!(result.result, BotCompletion::Timeout),
+        _ => panic!("expected rasa result"),
+    }
+}
+
+#[test]
+fn conference_status() {
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/conference-status.json"))
+            .unwrap();
+    match Request::decode(&HookKind::verb("conference", "statusHook"), &data).unwrap() {
+        Request::ConferenceStatus(status) => {
+            assert_eq!(status.event, ConferenceStatusEvent::StartTalking);
+            assert_eq!(status.members, 3);
+            assert_eq!(status.member_id.as_deref(), Some("17"));
+        }
+        _ => panic!("expected conference status"),
+    }
+
+    let mut data = data;
+    data["event"] = "mute".into();
+    match Request::decode(&HookKind::verb("conference", "statusHook"), &data).unwrap() {
+        Request::ConferenceStatus(status) => {
+            assert_eq!(status.event, ConferenceStatusEvent::Unknown)
+        }
+        _ => panic!("expected conference status"),
+    }
+
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/conference-wait.json"))
+            .unwrap();
+    for hook in ["enterHook", "waitHook"] {
+        match Request::decode(&HookKind::verb("conference", hook), &data).unwrap() {
+            Request::ConferenceWait(wait) => {
+                assert_eq!(wait.friendly_name.as_deref(), Some("sales-huddle"));
+                assert_eq!(wait.members, Some(0));
+                assert_eq!(wait.metadata["direction"], "inbound");
+            }
+            _ => panic!("expected conference wait"),
+        }
+    }
+}
+
+#[test]
+fn queue_payloads() {
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/enqueue-wait.json")).unwrap();
+    match Request::decode(&HookKind::verb("enqueue", "waitHook"), &data).unwrap() {
+        Request::QueueWait(wait) => {
+            assert_eq!(wait.queue_position, 2);
+            assert_eq!(wait.time_waited().as_secs(), 95);
+        }
+        _ => panic!("expected queue wait"),
+    }
+
+    let left: EnqueueOutcome = serde_json::from_str(
+        r#"{"call_sid": "9f6a", "queue_sid": "support", "queue_result": "leave", "queue_time": 1}"#,
+    )
+    .unwrap();
+    assert!(left.left());
+    assert_eq!(left.time_in_queue().as_secs(), 1);
+
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/dequeue-action.json"))
+            .unwrap();
+    match Request::decode(&HookKind::verb("dequeue", "actionHook"), &data).unwrap() {
+        Request::Dequeue(dequeue) => assert_eq!(dequeue.dequeue_result, DequeueResult::Bridged),
+        _ => panic!("expected dequeue outcome"),
+    }
+}
+
+#[test]
+fn transcription_event() {
+    let data: Value = serde_json::from_str(include_str!(
+        "../assets/fixtures/hooks/transcribe-transcription.json"
+    ))
+    .unwrap();
+    let hook = Request::decode(&HookKind::verb("transcribe", "transcriptionHook"), &data).unwrap();
+    let speech = match hook {
+        Request::Transcription(hook) => hook.speech,
+        _ => panic!("expected transcription"),
+    };
+    assert!(speech.is_final);
+    assert_eq!(speech.transcript(), Some("hello there how can I help"));
+    assert_eq!(speech.speaker(), Some("0"));
+    assert_eq!(speech.raw().unwrap()["type"], "Results");
+
+    let turns = speech.speaker_turns();
+    assert_eq!(turns.len(), 2);
+    assert_eq!(turns[1].speaker.as_deref(), Some("1"));
+    assert_eq!(turns[1].text, "how can I help");
+    assert_eq!(turns[1].channel_tag, Some(2));
+    assert_eq!(turns[1].end_time, Some(1.8));
+
+    let word: WordTiming =
+        serde_json::from_str(r#"{"word": "hi", "start": 0.1, "end": 0.4, "speaker": 0}"#).unwrap();
+    assert_eq!(word.start_time, Some(0.1));
+    assert_eq!(word.end_time, Some(0.4));
+    assert_eq!(word.speaker.as_deref(), Some("0"));
+
+    let raw: TranscriptionEvent = serde_json::from_str(
+        r#"{"is_final": false, "speaker_label": "spk_1", "vendor": {"name": "aws", "evt": {}}}"#,
+    )
+    .unwrap();
+    assert_eq!(raw.speaker(), Some("spk_1"));
+    assert!(raw.transcript().is_none());
+}
+
+#[test]
+fn inbound_message() {
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/messaging.json")).unwrap();
+    let inbound = match Request::decode(&HookKind::Messaging, &data).unwrap() {
+        Request::Message(inbound) => inbound,
+        _ => panic!("expected inbound message"),
+    };
+    assert_eq!(inbound.media.len(), 1);
+    let reply = inbound.reply("You have been unsubscribed".to_string());
+    assert_eq!(reply.from, "+441234567890");
+    assert_eq!(reply.to, "+447700900123");
+    assert_eq!(reply.carrier.as_deref(), Some("voxbone"));
+
+    let data: Value =
+        serde_json::from_str(include_str!("../assets/fixtures/hooks/message-action.json"))
+            .unwrap();
+    match Request::decode(&HookKind::verb("message", "actionHook"), &data).unwrap() {
+        Request::MessageStatus(status) => {
+            assert_eq!(status.message_status, MessageStatus::NoCarriers)
         }
+        _ => panic!("expected message status"),
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn decode_by_hook() {
+    let dial: Value =
+        serde_json::from_str(include_str!("../assets/subsequent-dial-request.json")).unwrap();
+    let request = Request::decode(&HookKind::verb("dial", "actionHook"), &dial).unwrap();
+    assert!(matches!(request, Request::Dial(_)));
+
+    let mut gather: Value =
+        serde_json::from_str(include_str!("../assets/subsequent-request.json")).unwrap();
+    let error = Request::decode(&HookKind::verb("gather", "actionHook"), &gather)
+        .err()
+        .unwrap();
+    assert_eq!(error.expected, "SubsequentRequest");
+    assert_eq!(error.missing_field.as_deref(), Some("customerData.x_cid"));
+    assert_eq!(
+        error.to_string(),
+        "gather actionHook payload is not a SubsequentRequest: \
+         missing field `customerData.x_cid`"
+    );
+
+    gather.as_object_mut().unwrap().remove("customerData");
+    let error = Request::decode(&HookKind::verb("gather", "actionHook"), &gather)
+        .err()
+        .unwrap();
+    assert_eq!(error.missing_field.as_deref(), Some("customerData"));
+
+    let child: Value =
+        serde_json::from_str(include_str!("../assets/outbound-child-event.json")).unwrap();
+    match Request::decode(&HookKind::CallStatus, &child).unwrap() {
+        Request::BEvent(event) => {
+            assert_eq!(event.sbc_callid, None);
+            assert_eq!(event.metadata["caller_id"], "+441234567890");
+        }
+        _ => panic!("expected a child leg status"),
+    }
+
+    let mut child = child;
+    child.as_object_mut().unwrap().remove("trace_id");
+    let error = Request::decode(&HookKind::CallStatus, &child).err().unwrap();
+    assert_eq!(error.expected, "ChildEvent");
+    assert_eq!(error.missing_field.as_deref(), Some("trace_id"));
+}
+
+#[test]
+fn sip_helpers() {
+    let mut initial: InitialRequest =
+        serde_json::from_str(include_str!("../assets/initial-request.json")).unwrap();
+    assert_eq!(
+        initial.try_get_contact_ip().unwrap(),
+        "172.31.1.12".parse::<IpAddr>().unwrap()
+    );
+    for (contact, ip) in [
+        ("\"Bob\" <sip:+441234@10.0.0.1:5060;transport=udp>", "10.0.0.1"),
+        ("<sips:10.0.0.2;transport=tls>", "10.0.0.2"),
+        ("sip:alice@10.0.0.3", "10.0.0.3"),
+        ("<sip:[2001:db8::1]:5060>", "2001:db8::1"),
+    ] {
+        initial.sip.headers.contact = contact.to_string();
+        assert_eq!(
+            initial.try_get_contact_ip().unwrap(),
+            ip.parse::<IpAddr>().unwrap()
+        );
+    }
+    initial.sip.headers.contact = "<sip:pbx.example.com:5060>".to_string();
+    assert!(matches!(
+        initial.try_get_contact_ip(),
+        Err(Error::InvalidContact(_))
+    ));
+
+    initial.sip.headers.x_forwarded_for = "10.1.2.3, 192.0.2.1".to_string();
+    assert!(initial.sip.try_has_proxy(vec!["10.0.0.0/8"]).unwrap());
+    assert!(matches!(
+        initial.try_get_tenant_type(vec!["192.168.0.0/16"]),
+        Ok(TenantType::TRUNK)
+    ));
+    assert!(matches!(
+        initial.sip.try_has_proxy(vec!["10.0.0.256/8"]),
+        Err(Error::InvalidSubnet(subnet)) if subnet == "10.0.0.256/8"
+    ));
+    for (forwarded_for, proxy) in [
+        ("2001:db8::1", false),
+        ("[2001:db8::1]:5060", false),
+        ("10.1.2.3:5060", true),
+    ] {
+        initial.sip.headers.x_forwarded_for = forwarded_for.to_string();
+        assert_eq!(initial.sip.try_has_proxy(vec!["10.0.0.0/8"]).unwrap(), proxy);
+    }
+    initial.sip.headers.x_forwarded_for = "2001:db8::1".to_string();
+    assert!(initial.sip.try_has_proxy(vec!["2001:db8::/32"]).unwrap());
+    assert!(matches!(
+        initial.sip.try_has_proxy(vec!["2001:db8::zz/32"]),
+        Err(Error::InvalidSubnet(_))
+    ));
+    initial.sip.headers.x_forwarded_for = "[2001:db8::1".to_string();
+    assert!(matches!(
+        initial.sip.try_has_proxy(vec!["10.0.0.0/8"]),
+        Err(Error::InvalidAddress(_))
+    ));
+    initial.sip.headers.x_forwarded_for = "unknown".to_string();
+    let error = initial.sip.try_has_proxy(vec!["10.0.0.0/8"]).unwrap_err();
+    assert_eq!(error.to_string(), "invalid ip address `unknown`");
+    assert!(!initial.sip.try_has_proxy(vec![]).unwrap());
+    assert!(matches!(
+        initial.try_get_tenant_type(vec![]),
+        Ok(TenantType::TRUNK)
+    ));
+}
+
+#[test]
+fn sip_request_within_dialog() {
+    use crate::payload::ws::{SipRequest, WebsocketReply, WebsocketRequest};
+
+    let request: WebsocketRequest = serde_json::from_str(
+        r#"{
+            "type": "verb:hook",
+            "msgid": "7c1d",
+            "call_sid": "9f6a",
+            "hook": "/sip-info",
+            "data": {
+                "call_sid": "9f6a",
+                "call_id": "1dfb7a86",
+                "direction": "inbound",
+                "from": "+447777777777",
+                "to": "+441234567890",
+                "account_sid": "7a2cf321",
+                "application_sid": "62b475c3",
+                "call_status": "in-progress",
+                "sip_method": "INFO",
+                "sip_body": "Signal=5",
+                "sip_headers": { "Content-Type": "application/dtmf-relay" }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let hook = match request {
+        WebsocketRequest::VerbHook(hook) => hook,
+        _ => panic!("expected verb:hook"),
+    };
+    let info = match hook.decode(&HookKind::SipRequestWithinDialog) {
+        Ok(Request::SipRequestWithinDialog(info)) => info,
+        _ => panic!("expected sip request within dialog"),
+    };
+    assert_eq!(info.content_type(), Some("application/dtmf-relay"));
+    assert_eq!(info.metadata["call_status"], "in-progress");
+
+    let reply: WebsocketReply = info.respond(SIPStatus::Ok).as_ack(&hook.msgid).into();
+    let value: serde_json::Value = serde_json::from_str(&reply.json()).unwrap();
+    assert_eq!(value["type"], "ack");
+    assert_eq!(value["msgid"], "7c1d");
+    assert_eq!(value["data"]["status"], 200);
+
+    let notify: WebsocketReply = SipRequest::new(SipMethod::NOTIFY, "ready".to_string())
+        .content_type(Some("text/plain".to_string()))
+        .clone()
+        .into();
+    let value: serde_json::Value = serde_json::from_str(&notify.json()).unwrap();
+    assert_eq!(value["command"], "sip:request");
+    assert_eq!(value["data"]["method"], "NOTIFY");
+}
+
+#[test]
+fn gather_result() {
+    use crate::payload::ws::WebsocketRequest;
+
+    let request: WebsocketRequest = serde_json::from_str(
+        r#"{
+            "type": "verb:hook",
+            "msgid": "b21f",
+            "call_sid": "9f6a",
+            "hook": "/gather/main-menu",
+            "data": {
+                "call_sid": "9f6a",
+                "direction": "inbound",
+                "from": "+447777777777",
+                "to": "+441234567890",
+                "call_id": "4ebb574f",
+                "sip_status": 200,
+                "call_status": "in-progress",
+                "account_sid": "66acafce",
+                "trace_id": "49d4e9a4",
+                "application_sid": "db7ba948",
+                "fs_sip_address": "172.31.1.74:5060",
+                "customerData": { "x_cid": "be36d66" },
+                "reason": "speechDetected",
+                "speech": {
+                    "language_code": "en-GB",
+                    "channel_tag": 1,
+                    "is_final": true,
+                    "alternatives": [
+                        { "confidence": 0.92, "transcript": "billing" },
+                        { "confidence": 0.41, "transcript": "building" }
+                    ]
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let hook = match request {
+        WebsocketRequest::VerbHook(hook) => hook,
+        _ => panic!("expected verb:hook"),
+    };
+    assert!(hook.gather_result("/dial-complete").is_none());
+
+    let result = hook.gather_result("/gather/main-menu").unwrap().unwrap();
+    assert_eq!(result.transcript(), Some("billing"));
+    match result {
+        GatherResult::Speech { speech } => {
+            assert_eq!(speech.language(), Some("en-GB"));
+            assert_eq!(speech.confidence(), Some(0.92));
+            assert_eq!(speech.alternatives.len(), 2);
+        }
+        _ => panic!("expected speech"),
+    }
+
+    let timeout: GatherResult =
+        serde_json::from_str(r#"{"reason": "timeout", "call_sid": "9f6a"}"#).unwrap();
+    assert!(matches!(timeout, GatherResult::Timeout));
+    let digits: GatherResult =
+        serde_json::from_str(r#"{"reason": "dtmfDetected", "digits": "12"}"#).unwrap();
+    assert_eq!(digits.digits(), Some("12"));
+
+    let other: GatherResult = serde_json::from_str(
+        r#"{"reason": "stt-low-confidence", "call_sid": "9f6a", "speech": {"is_final": true}}"#,
+    )
+    .unwrap();
+    match &other {
+        GatherResult::Other { reason, data } => {
+            assert_eq!(reason, "stt-low-confidence");
+            assert_eq!(data["call_sid"], "9f6a");
+        }
+        _ => panic!("expected other"),
+    }
+    let value = serde_json::to_value(&other).unwrap();
+    assert_eq!(value["reason"], "stt-low-confidence");
+    assert_eq!(value["speech"]["is_final"], true);
+    assert!(serde_json::from_str::<GatherResult>(r#"{"reason": "dtmfDetected"}"#).is_err());
+}
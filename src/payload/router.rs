@@ -0,0 +1,285 @@
+use crate::payload::rest::{HookKind, Request};
+use crate::payload::ws::SessionVerbHook;
+use crate::verbs::verb::Verb;
+use log::error;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+type HookHandler = Box<dyn Fn(&HookParams, Request) -> Vec<Verb> + Send + Sync>;
+
+/// Dispatches hooks to handlers by path, e.g. `/gather/:menu`.
+///
+/// Each handler gets the hook body decoded by `Request::decode` as the
+/// payload of the verb that owns the hook. The router learns which verb
+/// owns a hook from the verbs its handlers reply with, so a path should be
+/// used by one kind of hook. Hooks that no verb sets, such as the application
+/// `call_hook`, are declared with `kind`. Hooks that match no route, or whose
+/// body fails to decode, get the default reply.
+pub struct HookRouter {
+    routes: Vec<(Vec<Segment>, HookHandler)>,
+    default_reply: Vec<Verb>,
+    hooks: Mutex<HashMap<String, HookKind>>,
+}
+
+enum Segment {
+    Literal(String),
+    Param(String),
+}
+
+/// Values captured from the `:name` segments of a route.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HookParams {
+    pub path: String,
+    /// The hook the body was decoded as.
+    pub kind: HookKind,
+    params: HashMap<String, String>,
+}
+
+impl HookParams {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(|value| value.as_str())
+    }
+}
+
+impl Default for HookRouter {
+    fn default() -> HookRouter {
+        HookRouter::new()
+    }
+}
+
+impl HookRouter {
+    pub fn new() -> HookRouter {
+        HookRouter {
+            routes: vec![],
+            default_reply: vec![],
+            hooks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Routes are tried in the order they were added.
+    pub fn route<F>(&mut self, pattern: &str, handler: F) -> &mut HookRouter
+    where
+        F: Fn(&HookParams, Request) -> Vec<Verb> + Send + Sync + 'static,
+    {
+        let segments = segments(pattern)
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(name) => Segment::Param(name.to_string()),
+                None => Segment::Literal(segment.to_string()),
+            })
+            .collect();
+        self.routes.push((segments, Box::new(handler)));
+        self
+    }
+
+    /// Declares the kind of a hook that no verb sets, e.g. `HookKind::Call`
+    /// for the application `call_hook`.
+    pub fn kind(&mut self, hook: &str, kind: HookKind) -> &mut HookRouter {
+        self.hooks
+            .get_mut()
+            .unwrap()
+            .insert(hook_path(hook).to_string(), kind);
+        self
+    }
+
+    pub fn default_reply(&mut self, verbs: Vec<Verb>) -> &mut HookRouter {
+        self.default_reply = verbs;
+        self
+    }
+
+    /// The kind of `hook`, from the declared hooks and the verbs replied so
+    /// far. A hook that no verb set is the `sipRequestWithinDialogHook` if it
+    /// carries a `sip_method`.
+    pub fn hook_kind(&self, hook: &str, data: &Value) -> HookKind {
+        let hooks = self.hooks.lock().unwrap();
+        match hooks.get(hook_path(hook)) {
+            Some(kind) => kind.clone(),
+            None if data.get("sip_method").is_some() => HookKind::SipRequestWithinDialog,
+            None => HookKind::Other,
+        }
+    }
+
+    /// Dispatches a hook by path (or full url) with its raw body.
+    pub fn dispatch(&self, hook: &str, data: &Value) -> Vec<Verb> {
+        let kind = self.hook_kind(hook, data);
+        self.dispatch_as(&kind, hook, data)
+    }
+
+    /// Dispatches a hook whose kind is already known.
+    pub fn dispatch_as(&self, kind: &HookKind, hook: &str, data: &Value) -> Vec<Verb> {
+        let path = hook_path(hook);
+        let verbs = self
+            .routes
+            .iter()
+            .find_map(|(pattern, handler)| Some((matches(pattern, path, kind)?, handler)))
+            .and_then(|(params, handler)| match Request::decode(kind, data) {
+                Ok(request) => Some(handler(&params, request)),
+                Err(e) => {
+                    error!("{}: {}", path, e);
+                    None
+                }
+            })
+            .unwrap_or_else(|| self.default_reply.clone());
+        self.track(&verbs);
+        verbs
+    }
+
+    /// Dispatches a websocket `verb:hook`, using the kind `CallSession` set
+    /// on it when it has one.
+    pub fn verb_hook(&self, hook: &SessionVerbHook) -> Vec<Verb> {
+        match &hook.kind {
+            HookKind::Other => self.dispatch(&hook.hook, &hook.data),
+            kind => self.dispatch_as(kind, &hook.hook, &hook.data),
+        }
+    }
+
+    /// Dispatches an http webhook posted to `path` with the body as received.
+    pub fn webhook(&self, path: &str, body: &Value) -> Vec<Verb> {
+        self.dispatch(path, body)
+    }
+
+    fn track(&self, verbs: &[Verb]) {
+        let mut hooks = self.hooks.lock().unwrap();
+        for (hook, kind) in verbs.iter().flat_map(Verb::hooks) {
+            hooks.insert(hook_path(&hook).to_string(), kind);
+        }
+    }
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+/// Strips the scheme, host and query string from a hook.
+pub(crate) fn hook_path(hook: &str) -> &str {
+    let path = match hook.find("://") {
+        Some(scheme) => {
+            let rest = &hook[scheme + 3..];
+            rest.find('/').map(|host| &rest[host..]).unwrap_or("/")
+        }
+        None => hook,
+    };
+    path.split(['?', '#']).next().unwrap_or(path)
+}
+
+fn matches(pattern: &[Segment], path: &str, kind: &HookKind) -> Option<HookParams> {
+    let mut params = HashMap::new();
+    let mut segments = segments(path);
+    for expected in pattern {
+        let segment = segments.next()?;
+        match expected {
+            Segment::Literal(literal) if literal == segment => {}
+            Segment::Literal(_) => return None,
+            Segment::Param(name) => {
+                params.insert(name.clone(), segment.to_string());
+            }
+        }
+    }
+    if segments.next().is_some() {
+        return None;
+    }
+    Some(HookParams {
+        path: path.to_string(),
+        kind: kind.clone(),
+        params,
+    })
+}
+
+#[test]
+fn hook_router() {
+    use crate::payload::ws::WebsocketRequest;
+    use crate::verbs::dial::Dial;
+    use crate::verbs::gather::Gather;
+    use crate::verbs::hangup::Hangup;
+    use crate::verbs::say::Say;
+    use crate::verbs::target::Phone;
+    use serde_json::json;
+
+    let mut router = HookRouter::new();
+    router
+        .kind("/", HookKind::Call)
+        .route("/", |_, request| match request {
+            Request::Initial(_) => {
+                let mut gather = Gather::new("/gather/sales");
+                gather.digits();
+                gather.into()
+            }
+            _ => vec![],
+        })
+        .route("/gather/:menu", |params, request| match request {
+            Request::Subsequent(result) => {
+                let text = format!(
+                    "{} {}",
+                    params.get("menu").unwrap(),
+                    result.digits.unwrap_or_default()
+                );
+                let mut dial = Dial::new("+441234567890", Phone::new("+15551234".into()).into());
+                dial.action_hook = Some("/dial/complete".to_string());
+                vec![Say::new(text).into(), dial.into()]
+            }
+            _ => vec![],
+        })
+        .route("/dial/complete", |params, request| match request {
+            Request::Dial(dial) if dial.dial_call_status == "completed" => vec![],
+            Request::Dial(_) => Hangup::hangup().into(),
+            _ => panic!("expected a dial outcome for {:?}", params.kind),
+        })
+        .default_reply(Say::new("Sorry".to_string()).into());
+
+    let say = |verbs: Vec<Verb>| match &verbs[..] {
+        [Verb::Say(say), ..] => say.text.clone(),
+        _ => panic!("expected a say"),
+    };
+
+    let initial: Value =
+        serde_json::from_str(include_str!("../assets/initial-request.json")).unwrap();
+    let verbs = router.webhook("https://app.example.com/", &initial);
+    assert!(matches!(&verbs[..], [Verb::Gather(_)]));
+    assert_eq!(
+        router.hook_kind("/gather/sales", &json!({})),
+        HookKind::verb("gather", "actionHook")
+    );
+
+    let request: WebsocketRequest = serde_json::from_value(json!({
+        "type": "verb:hook",
+        "msgid": "b21f",
+        "call_sid": "9f6a",
+        "hook": "/gather/sales?attempt=2",
+        "data": {
+            "call_sid": "9f6a",
+            "direction": "inbound",
+            "from": "+447777777777",
+            "to": "+441234567890",
+            "call_id": "4ebb574f",
+            "sip_status": 200,
+            "call_status": "in-progress",
+            "account_sid": "66acafce",
+            "trace_id": "49d4e9a4",
+            "application_sid": "db7ba948",
+            "fs_sip_address": "172.31.1.74:5060",
+            "customerData": { "x_cid": "be36d66" },
+            "reason": "dtmfDetected",
+            "digits": "1"
+        }
+    }))
+    .unwrap();
+    match request {
+        WebsocketRequest::VerbHook(hook) => assert_eq!(say(router.verb_hook(&hook)), "sales 1"),
+        _ => panic!("expected verb hook"),
+    }
+
+    let mut dial: Value =
+        serde_json::from_str(include_str!("../assets/subsequent-dial-request.json")).unwrap();
+    dial["dial_call_status"] = "no-answer".into();
+    let verbs = router.webhook("https://app.example.com/dial/complete", &dial);
+    assert!(matches!(&verbs[..], [Verb::Hangup(_)]));
+
+    // A body that does not decode as the hook's payload gets the default reply.
+    let timeout = json!({"call_sid": "9f6a", "reason": "timeout"});
+    assert_eq!(say(router.dispatch("/dial/complete", &timeout)), "Sorry");
+    assert_eq!(say(router.dispatch("/gather", &timeout)), "Sorry");
+    assert_eq!(
+        say(router.dispatch("/gather/sales/extra", &timeout)),
+        "Sorry"
+    );
+}
@@ -0,0 +1,664 @@
+use crate::error::Error;
+use crate::payload::rest::{
+    HookKind, Request, SipRequestWithinDialog, SipRequestWithinDialogResponse,
+};
+use crate::payload::router::hook_path;
+use crate::payload::ws::{
+    Ack, Command, CommandValue, SessionCallStatus, SessionClose, SessionError, SessionLlmEvent,
+    SessionLlmToolCall, SessionNew, SessionReconnect, SessionRedirect, SessionVerbHook, SessionVerbStatus, Verbs,
+    WebsocketReply, WebsocketRequest,
+};
+use crate::verbs::verb::Verb;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use log::error;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+
+/// Callbacks for a single call over the jambonz websocket protocol.
+///
+/// Hooks that jambonz expects an ack for return the verbs to ack with;
+/// `CallSession` adds the matching `msgid`. Anything else to send
+/// (e.g. commands) is queued on the `SessionContext`.
+pub trait SessionHandler {
+    fn on_new(
+        &mut self,
+        context: &mut SessionContext,
+        session: SessionNew,
+    ) -> impl Future<Output = Vec<Verb>>;
+
+    fn on_verb_hook(
+        &mut self,
+        context: &mut SessionContext,
+        hook: SessionVerbHook,
+    ) -> impl Future<Output = Vec<Verb>> {
+        async { vec![] }
+    }
+
+    /// A `sipRequestWithinDialogHook`, acked with a SIP response rather than verbs.
+    fn on_sip_request(
+        &mut self,
+        context: &mut SessionContext,
+        request: SipRequestWithinDialog,
+    ) -> impl Future<Output = SipRequestWithinDialogResponse> {
+        async { SipRequestWithinDialogResponse::ok() }
+    }
+
+    fn on_redirect(
+        &mut self,
+        context: &mut SessionContext,
+        redirect: SessionRedirect,
+    ) -> impl Future<Output = Vec<Verb>> {
+        async { vec![] }
+    }
+
+    fn on_reconnect(
+        &mut self,
+        context: &mut SessionContext,
+        reconnect: SessionReconnect,
+    ) -> impl Future<Output = Vec<Verb>> {
+        async { vec![] }
+    }
+
+    fn on_call_status(
+        &mut self,
+        context: &mut SessionContext,
+        status: SessionCallStatus,
+    ) -> impl Future<Output = ()> {
+        async {}
+    }
+
+    fn on_verb_status(
+        &mut self,
+        context: &mut SessionContext,
+        status: SessionVerbStatus,
+    ) -> impl Future<Output = ()> {
+        async {}
+    }
+
+    fn on_llm_event(
+        &mut self,
+        context: &mut SessionContext,
+        event: SessionLlmEvent,
+    ) -> impl Future<Output = ()> {
+        async {}
+    }
+
+    /// Reply by queuing `tool_call.data.output(..)` on the context.
+    fn on_llm_tool_call(
+        &mut self,
+        context: &mut SessionContext,
+        tool_call: SessionLlmToolCall,
+    ) -> impl Future<Output = ()> {
+        async {}
+    }
+
+    /// A `jambonz:error` frame. Logged unless overridden.
+    fn on_error(
+        &mut self,
+        context: &mut SessionContext,
+        error: SessionError,
+    ) -> impl Future<Output = ()> {
+        async move {
+            error!(
+                "jambonz error: {} (verb: {:?}, hook: {:?})",
+                error.data.error, error.data.verb, error.data.hook
+            );
+        }
+    }
+
+    /// The session ended. `close` is empty if the stream ended without a close frame.
+    fn on_close(
+        &mut self,
+        context: &mut SessionContext,
+        close: SessionClose,
+    ) -> impl Future<Output = ()> {
+        async {}
+    }
+}
+
+/// State shared with the handler for the lifetime of the call.
+pub struct SessionContext {
+    call_sid: Option<String>,
+    pending: Vec<WebsocketReply>,
+    /// Hook paths of the verbs sent so far, by the kind of payload they post.
+    hooks: HashMap<String, HookKind>,
+}
+
+impl SessionContext {
+    fn new() -> SessionContext {
+        SessionContext {
+            call_sid: None,
+            pending: vec![],
+            hooks: HashMap::new(),
+        }
+    }
+
+    /// The kind of `hook`, from the verbs sent so far. A hook that no verb
+    /// set is the `sipRequestWithinDialogHook` if it carries a `sip_method`.
+    pub fn hook_kind(&self, hook: &str, data: &Value) -> HookKind {
+        match self.hooks.get(hook_path(hook)) {
+            Some(kind) => kind.clone(),
+            None if data.get("sip_method").is_some() => HookKind::SipRequestWithinDialog,
+            None => HookKind::Other,
+        }
+    }
+
+    fn track(&mut self, reply: &WebsocketReply) {
+        let verbs = match reply {
+            WebsocketReply::Ack(ack) => &ack.verbs.data,
+            WebsocketReply::Command(Command {
+                command_type: CommandValue::Redirect(redirect),
+            }) => &redirect.verbs.data,
+            _ => return,
+        };
+        for (hook, kind) in verbs.iter().flat_map(Verb::hooks) {
+            self.hooks.insert(hook_path(&hook).to_string(), kind);
+        }
+    }
+
+    /// Set once `session:new` has been received.
+    pub fn call_sid(&self) -> Option<&str> {
+        self.call_sid.as_deref()
+    }
+
+    /// Queues a reply, sent after the ack for the frame being handled.
+    pub fn send<R: Into<WebsocketReply>>(&mut self, reply: R) -> &mut SessionContext {
+        self.pending.push(reply.into());
+        self
+    }
+}
+
+/// A frame read from the websocket. Close frames come from the websocket
+/// layer rather than from a text message, so a peer cannot fake one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionFrame {
+    Text(String),
+    Close(SessionClose),
+}
+
+impl From<String> for SessionFrame {
+    fn from(text: String) -> SessionFrame {
+        SessionFrame::Text(text)
+    }
+}
+
+impl From<SessionClose> for SessionFrame {
+    fn from(close: SessionClose) -> SessionFrame {
+        SessionFrame::Close(close)
+    }
+}
+
+/// Why a `CallSession` stopped early.
+#[derive(Debug)]
+pub enum CallSessionError<E> {
+    /// A reply could not be encoded, so nothing was sent for it.
+    Encode(Error),
+    /// The sink failed to send a reply.
+    Send(E),
+}
+
+impl<E: fmt::Display> fmt::Display for CallSessionError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallSessionError::Encode(e) => write!(f, "could not encode reply: {}", e),
+            CallSessionError::Send(e) => write!(f, "could not send reply: {}", e),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for CallSessionError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CallSessionError::Encode(e) => Some(e),
+            CallSessionError::Send(e) => Some(e),
+        }
+    }
+}
+
+/// Drives a `SessionHandler` from a stream of incoming frames (`String`s
+/// or `SessionFrame`s), writing replies to a sink of outgoing text frames.
+pub struct CallSession<S, K> {
+    stream: S,
+    sink: K,
+    context: SessionContext,
+}
+
+impl<S, K> CallSession<S, K>
+where
+    S: Stream + Unpin,
+    S::Item: Into<SessionFrame>,
+    K: Sink<String> + Unpin,
+{
+    pub fn new(stream: S, sink: K) -> CallSession<S, K> {
+        CallSession {
+            stream,
+            sink,
+            context: SessionContext::new(),
+        }
+    }
+
+    /// Runs until the stream ends or yields `SessionFrame::Close` (or the
+    /// deprecated `WebsocketRequest::Close` message), then calls
+    /// `on_close`. Frames that cannot be decoded are logged, and acked with
+    /// no verbs if they carry a `msgid`.
+    pub async fn run<H: SessionHandler>(mut self, handler: &mut H) -> Result<(), CallSessionError<K::Error>> {
+        let mut close = SessionClose::default();
+        while let Some(frame) = self.stream.next().await {
+            match frame.into() {
+                SessionFrame::Text(text) => {
+                    if let Some(session_close) = self.handle_text(handler, &text).await? {
+                        close = session_close;
+                        break;
+                    }
+                }
+                SessionFrame::Close(session_close) => {
+                    close = session_close;
+                    break;
+                }
+            }
+        }
+        self.close(handler, close).await
+    }
+
+    /// Handles a single incoming text frame. For driving a session frame by
+    /// frame rather than from the stream; pass a close frame to `close`.
+    pub async fn handle<H: SessionHandler>(
+        &mut self,
+        handler: &mut H,
+        frame: &str,
+    ) -> Result<(), CallSessionError<K::Error>> {
+        self.handle_text(handler, frame).await.map(|_| ())
+    }
+
+    /// Handles a text frame, returning the close for a deprecated `Close` message.
+    async fn handle_text<H: SessionHandler>(
+        &mut self,
+        handler: &mut H,
+        frame: &str,
+    ) -> Result<Option<SessionClose>, CallSessionError<K::Error>> {
+        match serde_json::from_str::<WebsocketRequest>(frame) {
+            #[allow(deprecated)]
+            Ok(WebsocketRequest::Close) => Ok(Some(SessionClose::default())),
+            Ok(request) => self.dispatch(handler, request).await.map(|_| None),
+            Err(e) => {
+                error!("{}", e);
+                // jambonz waits for the ack of anything with a msgid.
+                match serde_json::from_str::<Value>(frame) {
+                    Ok(message) => match msgid(&message) {
+                        Some(msgid) => self.send(&ack(msgid, vec![])).await.map(|_| None),
+                        None => Ok(None),
+                    },
+                    Err(_) => Ok(None),
+                }
+            }
+        }
+    }
+
+    /// Calls `on_close` and sends anything it queued.
+    pub async fn close<H: SessionHandler>(
+        mut self,
+        handler: &mut H,
+        close: SessionClose,
+    ) -> Result<(), CallSessionError<K::Error>> {
+        handler.on_close(&mut self.context, close).await;
+        for reply in std::mem::take(&mut self.context.pending) {
+            self.send(&reply).await?;
+        }
+        self.sink.flush().await.map_err(CallSessionError::Send)
+    }
+
+    async fn send(&mut self, reply: &WebsocketReply) -> Result<(), CallSessionError<K::Error>> {
+        let text = reply.try_json().map_err(CallSessionError::Encode)?;
+        self.sink.send(text).await.map_err(CallSessionError::Send)
+    }
+
+    async fn dispatch<H: SessionHandler>(
+        &mut self,
+        handler: &mut H,
+        request: WebsocketRequest,
+    ) -> Result<(), CallSessionError<K::Error>> {
+        let context = &mut self.context;
+        let ack = match request {
+            WebsocketRequest::SessionNew(session) => {
+                context.call_sid = Some(session.call_sid.clone());
+                let msgid = session.msgid.clone();
+                Some(ack(&msgid, handler.on_new(context, session).await))
+            }
+            WebsocketRequest::VerbHook(mut hook) => {
+                let msgid = hook.msgid.clone();
+                hook.kind = context.hook_kind(&hook.hook, &hook.data);
+                let request = match hook.kind {
+                    HookKind::SipRequestWithinDialog => hook.request().ok(),
+                    _ => None,
+                };
+                match request {
+                    Some(Request::SipRequestWithinDialog(request)) => {
+                        let response = handler.on_sip_request(context, request).await;
+                        Some(WebsocketReply::SipRequestAck(response.as_ack(&msgid)))
+                    }
+                    _ => Some(ack(&msgid, handler.on_verb_hook(context, hook).await)),
+                }
+            }
+            WebsocketRequest::SessionRedirect(redirect) => {
+                let msgid = redirect.msgid.clone();
+                Some(ack(&msgid, handler.on_redirect(context, redirect).await))
+            }
+            WebsocketRequest::SessionReconnect(reconnect) => {
+                let msgid = reconnect.msgid.clone();
+                Some(ack(&msgid, handler.on_reconnect(context, reconnect).await))
+            }
+            WebsocketRequest::CallStatus(status) => {
+                handler.on_call_status(context, status).await;
+                None
+            }
+            WebsocketRequest::VerbStatus(status) => {
+                handler.on_verb_status(context, status).await;
+                None
+            }
+            WebsocketRequest::LlmEvent(event) => {
+                handler.on_llm_event(context, event).await;
+                None
+            }
+            WebsocketRequest::LlmToolCall(tool_call) => {
+                handler.on_llm_tool_call(context, tool_call).await;
+                None
+            }
+            WebsocketRequest::Error(session_error) => {
+                handler.on_error(context, session_error).await;
+                None
+            }
+            // Closes the session in `run`; `close` is up to the caller of `handle`.
+            #[allow(deprecated)]
+            WebsocketRequest::Close => None,
+            WebsocketRequest::Unknown(message) => {
+                error!("unhandled message: {}", message);
+                msgid(&message).map(|msgid| ack(msgid, vec![]))
+            }
+        };
+
+        if let Some(ack) = ack {
+            self.context.track(&ack);
+            self.send(&ack).await?;
+        }
+        for reply in std::mem::take(&mut self.context.pending) {
+            self.context.track(&reply);
+            self.send(&reply).await?;
+        }
+        Ok(())
+    }
+}
+
+fn msgid(message: &Value) -> Option<&str> {
+    message.get("msgid").and_then(Value::as_str)
+}
+
+fn ack(msgid: &str, verbs: Vec<Verb>) -> WebsocketReply {
+    WebsocketReply::Ack(Ack {
+        msgid: msgid.to_string(),
+        verbs: Verbs {
+            msg_id: msgid.to_string(),
+            data: verbs,
+        },
+    })
+}
+
+#[test]
+fn call_session() {
+    use crate::payload::ws::LlmUpdate;
+    use crate::verbs::gather::Gather;
+    use crate::verbs::hangup::Hangup;
+    use crate::verbs::say::Say;
+    use futures::channel::mpsc;
+    use futures::executor::block_on;
+    use serde_json::{Value, json};
+
+    struct Ivr {
+        hooks: Vec<String>,
+        errors: Vec<String>,
+        closed: Option<SessionClose>,
+    }
+
+    impl SessionHandler for Ivr {
+        async fn on_new(&mut self, context: &mut SessionContext, session: SessionNew) -> Vec<Verb> {
+            let mut gather = Gather::new("/menu");
+            gather.say(Some(Say::new("Press 1 for sales".to_string())));
+            gather.into()
+        }
+
+        async fn on_verb_hook(
+            &mut self,
+            context: &mut SessionContext,
+            hook: SessionVerbHook,
+        ) -> Vec<Verb> {
+            assert_eq!(hook.kind, HookKind::verb("gather", "actionHook"));
+            assert!(matches!(hook.request(), Ok(Request::Subsequent(_))));
+            self.hooks.push(hook.hook.clone());
+            context.send(LlmUpdate::new(json!({"type": "session.update"})));
+            Hangup::hangup().into()
+        }
+
+        async fn on_error(&mut self, context: &mut SessionContext, error: SessionError) {
+            self.errors.push(error.data.verb.unwrap_or_default());
+        }
+
+        async fn on_close(&mut self, context: &mut SessionContext, close: SessionClose) {
+            self.closed = Some(close);
+        }
+    }
+
+    let initial: Value =
+        serde_json::from_str(include_str!("../assets/initial-request.json")).unwrap();
+    let frames = vec![
+        json!({
+            "type": "session:new",
+            "msgid": "m1",
+            "call_sid": "c1",
+            "data": initial,
+        })
+        .to_string(),
+        "not json".to_string(),
+        json!({
+            "type": "verb:hook",
+            "msgid": "m2",
+            "call_sid": "c1",
+            "hook": "/menu",
+            "data": {
+                "call_sid": "c1",
+                "direction": "inbound",
+                "from": "+447777777777",
+                "to": "+441234567890",
+                "call_id": "4ebb574f",
+                "sip_status": 200,
+                "call_status": "in-progress",
+                "account_sid": "66acafce",
+                "trace_id": "49d4e9a4",
+                "application_sid": "db7ba948",
+                "fs_sip_address": "172.31.1.74:5060",
+                "customerData": { "x_cid": "be36d66" },
+                "reason": "dtmfDetected",
+                "digits": "1"
+            },
+        })
+        .to_string(),
+        json!({
+            "type": "jambonz:error",
+            "call_sid": "c1",
+            "data": {"error": "invalid verb", "verb": "gather", "hook": "/menu"}
+        })
+        .to_string(),
+    ];
+    let mut frames: Vec<SessionFrame> = frames.into_iter().map(SessionFrame::from).collect();
+    // A text frame cannot close the session; only the websocket layer can.
+    frames.insert(2, json!({"type": "close", "code": 1000}).to_string().into());
+    // Frames that cannot be handled are still acked, with no verbs.
+    frames.push(json!({"type": "verb:hook", "msgid": "m4", "call_sid": "c1"}).to_string().into());
+    frames.push(json!({"type": "session:transfer", "msgid": "m5"}).to_string().into());
+    frames.push(SessionClose::new(Some(1011), Some("server error".to_string())).into());
+    frames.push(
+        json!({"type": "session:new", "msgid": "m3", "call_sid": "c1", "data": initial})
+            .to_string()
+            .into(),
+    );
+    let (tx, rx) = mpsc::unbounded();
+    let mut ivr = Ivr {
+        hooks: vec![],
+        errors: vec![],
+        closed: None,
+    };
+    block_on(CallSession::new(futures::stream::iter(frames), tx).run(&mut ivr)).unwrap();
+    let replies: Vec<Value> = block_on(rx.collect::<Vec<String>>())
+        .iter()
+        .map(|reply| serde_json::from_str(reply).unwrap())
+        .collect();
+
+    assert_eq!(ivr.hooks, vec!["/menu"]);
+    assert_eq!(ivr.errors, vec!["gather"]);
+    let closed = ivr.closed.unwrap();
+    assert_eq!(closed.code, Some(1011));
+    assert!(!closed.is_normal());
+    assert_eq!(replies.len(), 5);
+    assert_eq!(replies[0]["type"], "ack");
+    assert_eq!(replies[0]["data"][0]["verb"], "gather");
+    assert_eq!(replies[1]["msgid"], "m2");
+    assert_eq!(replies[1]["data"][0]["verb"], "hangup");
+    assert_eq!(replies[2]["command"], "llm:update");
+    assert_eq!(replies[3]["msgid"], "m4");
+    assert_eq!(replies[3]["data"], json!([]));
+    assert_eq!(replies[4]["msgid"], "m5");
+    assert_eq!(replies[4]["data"], json!([]));
+}
+
+#[test]
+fn dial_hooks() {
+    use crate::payload::rest::AmdEvent;
+    use futures::channel::mpsc;
+    use futures::executor::block_on;
+    use serde_json::{Value, json};
+
+    struct Dialer {
+        requests: Vec<(HookKind, &'static str)>,
+    }
+
+    impl SessionHandler for Dialer {
+        async fn on_new(
+            &mut self,
+            _context: &mut SessionContext,
+            _session: SessionNew,
+        ) -> Vec<Verb> {
+            let dial = json!({
+                "verb": "dial",
+                "actionHook": "/dial",
+                "callerId": "+441234567890",
+                "target": [{"type": "phone", "number": "+447777777777"}],
+                "amd": {"actionHook": "/amd"},
+                "transcribe": {
+                    "transcriptionHook": "/transcription",
+                    "recognizer": {"vendor": "google", "language": "en-GB"}
+                }
+            });
+            vec![serde_json::from_value(dial).unwrap()]
+        }
+
+        async fn on_verb_hook(
+            &mut self,
+            _context: &mut SessionContext,
+            hook: SessionVerbHook,
+        ) -> Vec<Verb> {
+            let request = match hook.request() {
+                Ok(Request::Amd(AmdEvent::MachineDetected { hint, .. })) => {
+                    assert_eq!(hint.as_deref(), Some("voicemail"));
+                    "amd"
+                }
+                Ok(Request::Transcription(_)) => "transcription",
+                Ok(Request::Dial(_)) => "dial",
+                _ => "other",
+            };
+            self.requests.push((hook.kind, request));
+            vec![]
+        }
+    }
+
+    let initial: Value =
+        serde_json::from_str(include_str!("../assets/initial-request.json")).unwrap();
+    let transcription: Value = serde_json::from_str(include_str!(
+        "../assets/fixtures/hooks/transcribe-transcription.json"
+    ))
+    .unwrap();
+    let mut amd = transcription.clone();
+    amd.as_object_mut().unwrap().remove("speech");
+    amd["type"] = json!("amd_machine_detected");
+    amd["reason"] = json!("hint");
+    amd["hint"] = json!("voicemail");
+    let dial: Value =
+        serde_json::from_str(include_str!("../assets/subsequent-dial-request.json")).unwrap();
+    let frame = |msgid: &str, hook: &str, data: &Value| {
+        json!({
+            "type": "verb:hook",
+            "msgid": msgid,
+            "call_sid": "c1",
+            "hook": hook,
+            "data": data,
+        })
+        .to_string()
+        .into()
+    };
+    let frames: Vec<SessionFrame> = vec![
+        json!({"type": "session:new", "msgid": "m1", "call_sid": "c1", "data": initial})
+            .to_string()
+            .into(),
+        frame("m2", "/amd", &amd),
+        frame("m3", "/transcription", &transcription),
+        frame("m4", "/dial", &dial),
+    ];
+    let (tx, _rx) = mpsc::unbounded();
+    let mut dialer = Dialer { requests: vec![] };
+    block_on(CallSession::new(futures::stream::iter(frames), tx).run(&mut dialer)).unwrap();
+
+    assert_eq!(
+        dialer.requests,
+        vec![
+            (HookKind::verb("amd", "actionHook"), "amd"),
+            (HookKind::verb("transcribe", "transcriptionHook"), "transcription"),
+            (HookKind::verb("dial", "actionHook"), "dial"),
+        ]
+    );
+}
+
+#[test]
+fn deprecated_close() {
+    use futures::channel::mpsc;
+    use futures::executor::block_on;
+    use serde_json::{Value, json};
+
+    struct App {
+        closed: Vec<SessionClose>,
+    }
+
+    impl SessionHandler for App {
+        async fn on_new(
+            &mut self,
+            _context: &mut SessionContext,
+            _session: SessionNew,
+        ) -> Vec<Verb> {
+            vec![]
+        }
+
+        async fn on_close(&mut self, _context: &mut SessionContext, close: SessionClose) {
+            self.closed.push(close);
+        }
+    }
+
+    let initial: Value =
+        serde_json::from_str(include_str!("../assets/initial-request.json")).unwrap();
+    let session_new =
+        json!({"type": "session:new", "msgid": "m1", "call_sid": "c1", "data": initial}).to_string();
+    let frames = vec![session_new.clone(), json!({"type": "Close"}).to_string(), session_new];
+    let (tx, rx) = mpsc::unbounded();
+    let mut app = App { closed: vec![] };
+    block_on(CallSession::new(futures::stream::iter(frames), tx).run(&mut app)).unwrap();
+
+    assert_eq!(app.closed, vec![SessionClose::default()]);
+    assert_eq!(block_on(rx.collect::<Vec<String>>()).len(), 1);
+}
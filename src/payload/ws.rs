@@ -1,9 +1,15 @@
-use crate::payload::rest::{InitialRequest, Request};
+use crate::payload::rest::{
+    AmdEvent, ConferenceStatus, DecodeError, DequeueOutcome, DialOutcome, EnqueueOutcome,
+    GatherResult, HookKind, InitialRequest, MessageStatusCallback, QueueWaitRequest, Request,
+    SipRequestWithinDialogResponse, TranscriptionHook,
+};
 use crate::shared::shared::SIPStatus;
+use crate::shared::strict::{tagged, unknown, variant, Known};
 use crate::verbs::dub::DubData;
 use crate::verbs::play_say::PlaySay;
 use crate::verbs::verb::Verb;
-use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -27,20 +33,75 @@ pub enum RecordingRequest {
     Close,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-#[serde(tag = "type")]
-pub enum WebsocketRequest {
-    #[serde(rename = "session:new")]
-    SessionNew(SessionNew),
-    #[serde(rename = "session:redirect")]
-    SessionRedirect(SessionRedirect),
-    #[serde(rename = "session:reconnect")]
-    SessionReconnect(SessionReconnect),
-    #[serde(rename = "call:status")]
-    CallStatus(SessionCallStatus),
-    #[serde(rename = "verb:hook")]
-    VerbHook(SessionVerbHook),
-    Close,
+pub use request::WebsocketRequest;
+
+mod request {
+    // The derived `Serialize` matches on the deprecated `Close`.
+    #![allow(deprecated)]
+    use super::*;
+
+    #[derive(Serialize, Clone)]
+    #[serde(tag = "type")]
+    pub enum WebsocketRequest {
+        #[serde(rename = "session:new")]
+        SessionNew(SessionNew),
+        #[serde(rename = "session:redirect")]
+        SessionRedirect(SessionRedirect),
+        #[serde(rename = "session:reconnect")]
+        SessionReconnect(SessionReconnect),
+        #[serde(rename = "call:status")]
+        CallStatus(SessionCallStatus),
+        #[serde(rename = "verb:hook")]
+        VerbHook(SessionVerbHook),
+        #[serde(rename = "verb:status")]
+        VerbStatus(SessionVerbStatus),
+        #[serde(rename = "llm:event")]
+        LlmEvent(SessionLlmEvent),
+        #[serde(rename = "llm:tool-call")]
+        LlmToolCall(SessionLlmToolCall),
+        #[serde(rename = "jambonz:error")]
+        Error(SessionError),
+        /// Not sent by jambonz. `CallSession` treats it as a close with no code.
+        #[deprecated(note = "pass a SessionFrame::Close with the close code and reason to CallSession")]
+        Close,
+        /// A message type this crate does not model, kept as the raw JSON.
+        #[serde(untagged)]
+        Unknown(Value),
+    }
+}
+
+/// Falls back to `Unknown` only for message types not modelled above; a bad
+/// body for a known type is an error.
+impl<'de> Deserialize<'de> for WebsocketRequest {
+    fn deserialize<D>(deserializer: D) -> Result<WebsocketRequest, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (name, body) = tagged(deserializer, "type")?;
+        Ok(match name.as_str() {
+            "session:new" => WebsocketRequest::SessionNew(variant(body)?),
+            "session:redirect" => WebsocketRequest::SessionRedirect(variant(body)?),
+            "session:reconnect" => WebsocketRequest::SessionReconnect(variant(body)?),
+            "call:status" => WebsocketRequest::CallStatus(variant(body)?),
+            "verb:hook" => WebsocketRequest::VerbHook(variant(body)?),
+            "verb:status" => WebsocketRequest::VerbStatus(variant(body)?),
+            "llm:event" => WebsocketRequest::LlmEvent(variant(body)?),
+            "llm:tool-call" => WebsocketRequest::LlmToolCall(variant(body)?),
+            "jambonz:error" => WebsocketRequest::Error(variant(body)?),
+            #[allow(deprecated)]
+            "Close" => WebsocketRequest::Close,
+            _ => WebsocketRequest::Unknown(unknown("type", name, body)),
+        })
+    }
+}
+
+impl Known for WebsocketRequest {
+    fn unknown(&self) -> Option<&Value> {
+        match self {
+            WebsocketRequest::Unknown(message) => Some(message),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -94,46 +155,270 @@ pub struct SessionReconnect {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(try_from = "SessionCallStatusFrame")]
 pub struct SessionCallStatus {
     pub msgid: String,
     pub call_sid: String,
     pub b3: Option<String>,
     pub data: Request,
-    #[serde(rename = "data.call_status")]
+    /// Read from `data.call_status`.
+    #[serde(skip_serializing)]
     pub call_status: SessionCallStatusEnum,
 }
+
+#[derive(Deserialize)]
+struct SessionCallStatusFrame {
+    msgid: String,
+    call_sid: String,
+    b3: Option<String>,
+    data: Value,
+}
+
+impl TryFrom<SessionCallStatusFrame> for SessionCallStatus {
+    type Error = serde_json::Error;
+
+    fn try_from(frame: SessionCallStatusFrame) -> Result<Self, Self::Error> {
+        Ok(SessionCallStatus {
+            call_status: SessionCallStatusEnum::deserialize(&frame.data["call_status"])?,
+            data: Request::decode(&HookKind::CallStatus, &frame.data)
+                .map_err(serde::de::Error::custom)?,
+            msgid: frame.msgid,
+            call_sid: frame.call_sid,
+            b3: frame.b3,
+        })
+    }
+}
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SessionVerbHook {
     pub msgid: String,
     pub call_sid: String,
     pub b3: Option<String>,
     pub hook: String,
-    pub data: Request,
+    /// The hook body as received. Use `request` or `decode` to read it.
+    pub data: Value,
+    /// The kind of `hook`, set by `CallSession` from the verbs it has sent.
+    #[serde(skip)]
+    pub kind: HookKind,
+}
+
+impl SessionVerbHook {
+    /// Decodes the hook body as the payload posted by `kind`.
+    pub fn decode(&self, kind: &HookKind) -> Result<Request, DecodeError> {
+        Request::decode(kind, &self.data)
+    }
+
+    /// Decodes the hook body as the payload posted by `self.kind`.
+    pub fn request(&self) -> Result<Request, DecodeError> {
+        self.decode(&self.kind)
+    }
+
+    /// Decodes the hook body into any payload type.
+    pub fn data_as<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        T::deserialize(&self.data)
+    }
+
+    /// Decodes the hook body if this message was sent to `hook`.
+    pub fn data_for<T: DeserializeOwned>(&self, hook: &str) -> Option<serde_json::Result<T>> {
+        if self.hook == hook {
+            Some(self.data_as())
+        } else {
+            None
+        }
+    }
+
+    /// Decodes the result of the gather whose `actionHook` is `action_hook`.
+    /// Returns `None` if this hook is for a different verb.
+    pub fn gather_result(&self, action_hook: &str) -> Option<serde_json::Result<GatherResult>> {
+        self.data_for(action_hook)
+    }
+
+    /// Decodes the outcome of the dial whose `actionHook` is `action_hook`.
+    pub fn dial_outcome(&self, action_hook: &str) -> Option<serde_json::Result<DialOutcome>> {
+        self.data_for(action_hook)
+    }
+
+    /// Decodes an event from the amd whose `actionHook` is `action_hook`.
+    pub fn amd_event(&self, action_hook: &str) -> Option<serde_json::Result<AmdEvent>> {
+        self.data_for(action_hook)
+    }
+
+    /// Decodes the outcome of the enqueue whose `actionHook` is `action_hook`.
+    pub fn enqueue_outcome(&self, action_hook: &str) -> Option<serde_json::Result<EnqueueOutcome>> {
+        self.data_for(action_hook)
+    }
+
+    /// Decodes the queue position sent to the enqueue whose `waitHook` is `wait_hook`.
+    pub fn queue_wait(&self, wait_hook: &str) -> Option<serde_json::Result<QueueWaitRequest>> {
+        self.data_for(wait_hook)
+    }
+
+    /// Decodes the outcome of the dequeue whose `actionHook` is `action_hook`.
+    pub fn dequeue_outcome(&self, action_hook: &str) -> Option<serde_json::Result<DequeueOutcome>> {
+        self.data_for(action_hook)
+    }
+
+    /// Decodes the delivery status of the message whose `actionHook` is `action_hook`.
+    pub fn message_status(
+        &self,
+        action_hook: &str,
+    ) -> Option<serde_json::Result<MessageStatusCallback>> {
+        self.data_for(action_hook)
+    }
+
+    /// Decodes a transcript sent to the `transcriptionHook` named `transcription_hook`.
+    pub fn transcription(
+        &self,
+        transcription_hook: &str,
+    ) -> Option<serde_json::Result<TranscriptionHook>> {
+        self.data_for(transcription_hook)
+    }
+
+    /// Decodes an event from the conference whose `statusHook` is `status_hook`.
+    pub fn conference_status(
+        &self,
+        status_hook: &str,
+    ) -> Option<serde_json::Result<ConferenceStatus>> {
+        self.data_for(status_hook)
+    }
 }
 
+/// Sent by jambonz when a verb is invalid or a hook could not be run.
 #[derive(Serialize, Deserialize, Clone)]
-pub struct SessionVerbStatus {
+pub struct SessionError {
+    pub msgid: Option<String>,
+    pub call_sid: Option<String>,
+    pub data: JambonzError,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JambonzError {
+    #[serde(alias = "message")]
+    pub error: String,
+    /// The verb that caused the error, if any.
+    pub verb: Option<String>,
+    /// The hook that failed, if any.
+    pub hook: Option<String>,
+
+    #[serde(flatten)]
+    pub details: HashMap<String, Value>,
+}
+
+/// The websocket closed, with the close frame's code and reason if one was sent.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct SessionClose {
+    pub code: Option<u16>,
+    pub reason: Option<String>,
+}
+
+impl SessionClose {
+    pub fn new(code: Option<u16>, reason: Option<String>) -> SessionClose {
+        SessionClose { code, reason }
+    }
+
+    /// True for a normal closure (1000) or the peer going away (1001).
+    pub fn is_normal(&self) -> bool {
+        matches!(self.code, Some(1000) | Some(1001))
+    }
+}
+
+/// A vendor event forwarded from an llm verb's `eventHook`.
+/// `data` is the raw vendor event, e.g. an OpenAI `response.done`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionLlmEvent {
     pub msgid: String,
     pub call_sid: String,
     pub b3: Option<String>,
     pub hook: String,
-    pub data: HashMap<String, String>,
-    #[serde(rename = "data.id")]
-    pub data_id: String,
-    #[serde(rename = "data.verb")]
-    pub data_verb: String,
-    #[serde(rename = "data.status")]
-    pub data_status: DataStatus,
+    pub data: Value,
 }
 
+impl SessionLlmEvent {
+    pub fn event_type(&self) -> Option<&str> {
+        self.data.get("type").and_then(Value::as_str)
+    }
+}
+
+/// A function call requested by the model, sent to the llm verb's `toolHook`.
+/// Answer it with an `llm:tool-output` command carrying the same `tool_call_id`.
 #[derive(Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+pub struct SessionLlmToolCall {
+    pub msgid: String,
+    pub call_sid: String,
+    pub b3: Option<String>,
+    pub hook: String,
+    pub data: LlmToolCall,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LlmToolCall {
+    pub name: String,
+    pub tool_call_id: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+impl LlmToolCall {
+    pub fn output(&self, data: Value) -> LlmToolOutput {
+        LlmToolOutput::new(&self.tool_call_id, data)
+    }
+}
+
+/// Sent for each verb carrying an `id` once `Config::notify_events` is enabled.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionVerbStatus {
+    pub msgid: String,
+    pub call_sid: String,
+    pub b3: Option<String>,
+    pub hook: Option<String>,
+    pub data: VerbStatusData,
+}
+
+impl SessionVerbStatus {
+    /// The `id` of the verb this status refers to.
+    pub fn id(&self) -> Option<&str> {
+        self.data.id.as_deref()
+    }
+
+    pub fn status(&self) -> &DataStatus {
+        &self.data.status
+    }
+
+    /// Looks up the verb this status refers to in the verbs that were sent.
+    pub fn verb<'a>(&self, verbs: &'a Verbs) -> Option<&'a Verb> {
+        let id = self.id()?;
+        verbs.data.iter().find(|verb| verb.id() == Some(id))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VerbStatusData {
+    pub id: Option<String>,
+    #[serde(alias = "name")]
+    pub verb: Option<String>,
+    #[serde(alias = "event")]
+    pub status: DataStatus,
+
+    #[serde(flatten)]
+    pub metadata: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
 pub enum DataStatus {
     Begin,
     End,
+    StartPlayback,
+    StopPlayback,
+    KillPlayback,
+    Dtmf,
+    DtmfBargeinDetected,
+    SpeechBargeinDetected,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
 pub enum SessionCallStatusEnum {
     Trying,
     Ringing,
@@ -155,6 +440,10 @@ pub enum SessionCallStatusEnum {
 pub enum WebsocketReply {
     Ack(Ack),
     Command(Command),
+    /// Acks a `sipRequestWithinDialogHook` with the SIP response to send,
+    /// rather than a list of verbs.
+    #[serde(rename = "ack", skip_deserializing)]
+    SipRequestAck(SipRequestAck),
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -164,6 +453,12 @@ pub struct Ack {
     pub verbs: Verbs,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SipRequestAck {
+    pub msgid: String,
+    pub data: SipRequestWithinDialogResponse,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Command {
     #[serde(flatten)]
@@ -181,9 +476,12 @@ pub enum CommandType {
     Record,
     Whisper,
     Dub,
+    LlmToolOutput,
+    LlmUpdate,
+    SipRequest,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Clone)]
 #[serde(tag = "command")]
 #[serde(rename_all = "camelCase")]
 pub enum CommandValue {
@@ -205,6 +503,56 @@ pub enum CommandValue {
     Whisper(Whisper),
     #[serde(rename = "dub")]
     Dub(DubData),
+    #[serde(rename = "llm:tool-output")]
+    LlmToolOutput(LlmToolOutput),
+    #[serde(rename = "llm:update")]
+    LlmUpdate(LlmUpdate),
+    #[serde(rename = "sip:request")]
+    SipRequest(SipRequest),
+    /// A command this crate does not model, kept as the raw JSON.
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+/// Falls back to `Unknown` only for commands not modelled above; a bad body
+/// for a known command is an error.
+impl<'de> Deserialize<'de> for CommandValue {
+    fn deserialize<D>(deserializer: D) -> Result<CommandValue, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (name, body) = tagged(deserializer, "command")?;
+        Ok(match name.as_str() {
+            "redirect" => CommandValue::Redirect(variant(body)?),
+            "call:status" => CommandValue::CallStatus(variant(body)?),
+            "mute:status" => CommandValue::MuteStatus(variant(body)?),
+            "conf:mute-status" => CommandValue::ConferenceMuteStatus(variant(body)?),
+            "conf:hold-status" => CommandValue::ConferenceHoldStatus(variant(body)?),
+            "listen:status" => CommandValue::ListenStatus(variant(body)?),
+            "record" => CommandValue::Record(variant(body)?),
+            "whisper" => CommandValue::Whisper(variant(body)?),
+            "dub" => CommandValue::Dub(variant(body)?),
+            "llm:tool-output" => CommandValue::LlmToolOutput(variant(body)?),
+            "llm:update" => CommandValue::LlmUpdate(variant(body)?),
+            "sip:request" => CommandValue::SipRequest(variant(body)?),
+            _ => CommandValue::Unknown(unknown("command", name, body)),
+        })
+    }
+}
+
+impl Known for CommandValue {
+    fn unknown(&self) -> Option<&Value> {
+        match self {
+            CommandValue::Unknown(command) => Some(command),
+            _ => None,
+        }
+    }
+}
+
+impl Known for Command {
+    fn unknown(&self) -> Option<&Value> {
+        self.command_type.unknown()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -335,16 +683,85 @@ pub struct Whisper {
     pub data: Vec<PlaySay>,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LlmToolOutput {
+    pub tool_call_id: String,
+    pub data: Value,
+}
+
+impl LlmToolOutput {
+    pub fn new(tool_call_id: &str, data: Value) -> LlmToolOutput {
+        LlmToolOutput {
+            tool_call_id: tool_call_id.to_string(),
+            data,
+        }
+    }
+}
+
+/// Sends a vendor client event (e.g. an OpenAI `session.update`)
+/// to the llm verb that is currently running.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LlmUpdate {
+    pub data: Value,
+}
+
+impl LlmUpdate {
+    pub fn new(data: Value) -> LlmUpdate {
+        LlmUpdate { data }
+    }
+}
+
+/// Sends a SIP request (INFO, NOTIFY or MESSAGE) within the dialog of the current call.
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SipRequest {
     pub queue_command: bool,
     pub data: SipRequestData,
 }
+
+impl SipRequest {
+    pub fn new(method: SipMethod, content: String) -> SipRequest {
+        SipRequest {
+            queue_command: false,
+            data: SipRequestData {
+                method,
+                content_type: None,
+                content,
+                headers: None,
+            },
+        }
+    }
+
+    pub fn queue_command(&mut self, queue_command: bool) -> &mut SipRequest {
+        self.queue_command = queue_command;
+        self
+    }
+
+    pub fn content_type(&mut self, content_type: Option<String>) -> &mut SipRequest {
+        self.data.content_type = content_type;
+        self
+    }
+
+    pub fn header(&mut self, key: &str, value: &str) -> &mut SipRequest {
+        self.data
+            .headers
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn headers(&mut self, headers: Option<HashMap<String, String>>) -> &mut SipRequest {
+        self.data.headers = headers;
+        self
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SipRequestData {
     pub method: SipMethod,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub content_type: Option<String>,
     pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<HashMap<String, String>>,
 }
 
@@ -354,3 +771,63 @@ pub enum SipMethod {
     NOTIFY,
     MESSAGE,
 }
+
+#[test]
+fn llm_tool_call() {
+    let request: WebsocketRequest = serde_json::from_str(
+        r#"{
+            "type": "llm:tool-call",
+            "msgid": "5f2b",
+            "call_sid": "9f6a",
+            "hook": "/tool-call",
+            "data": {
+                "name": "get_weather",
+                "tool_call_id": "call_abc123",
+                "args": { "location": "London" }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let tool_call = match request {
+        WebsocketRequest::LlmToolCall(tool_call) => tool_call.data,
+        _ => panic!("expected llm:tool-call"),
+    };
+    assert_eq!(tool_call.name, "get_weather");
+    assert_eq!(tool_call.args["location"], "London");
+
+    let reply: WebsocketReply = tool_call
+        .output(serde_json::json!({ "temperature": 18 }))
+        .into();
+    let value: serde_json::Value = serde_json::from_str(&reply.try_json().unwrap()).unwrap();
+    assert_eq!(value["type"], "command");
+    assert_eq!(value["command"], "llm:tool-output");
+    assert_eq!(value["tool_call_id"], "call_abc123");
+    assert_eq!(value["data"]["temperature"], 18);
+}
+
+#[test]
+fn verb_status() {
+    let request: WebsocketRequest = serde_json::from_str(
+        r#"{
+            "type": "verb:status",
+            "msgid": "a81c",
+            "call_sid": "9f6a",
+            "data": {
+                "id": "welcome-prompt",
+                "verb": "say",
+                "status": "speech-bargein-detected",
+                "transcript": "sales please"
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let status = match request {
+        WebsocketRequest::VerbStatus(status) => status,
+        _ => panic!("expected verb:status"),
+    };
+    assert_eq!(status.id(), Some("welcome-prompt"));
+    assert_eq!(status.status(), &DataStatus::SpeechBargeinDetected);
+    assert_eq!(status.data.metadata["transcript"], "sales please");
+}
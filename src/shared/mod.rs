@@ -1 +1,2 @@
-pub mod shared;
\ No newline at end of file
+pub mod shared;
+pub mod strict;
\ No newline at end of file
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[repr(u16)]
 pub enum Direction {
     #[serde(rename = "inbound")]
@@ -90,4 +91,19 @@ pub enum SIPStatus {
     NotAcceptableGlobal = 606,
     Unwanted = 607,
     Rejected = 608,
-}
\ No newline at end of file
+}
+/// Sent as the numeric code, which the derive would describe as the variant names.
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for SIPStatus {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "SIPStatus".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "integer",
+            "minimum": 100,
+            "maximum": 699
+        })
+    }
+}
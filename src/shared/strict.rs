@@ -0,0 +1,129 @@
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+/// Deserializes like `T`, but fails where `T` would fall back to `Unknown`.
+///
+/// Verbs, websocket messages and commands that are not modelled deserialize
+/// to their `Unknown` variant, so that a newer jambonz does not break parsing.
+/// Tests usually want to know instead, e.g.
+/// `serde_json::from_str::<Strict<Vec<Verb>>>(json)`.
+pub struct Strict<T>(pub T);
+
+impl<T> Strict<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Implemented by types with an `Unknown` fallback.
+pub trait Known {
+    /// The raw JSON of the first unmodelled value, if any.
+    fn unknown(&self) -> Option<&Value>;
+}
+
+impl<T: Known> Known for Vec<T> {
+    fn unknown(&self) -> Option<&Value> {
+        self.iter().find_map(Known::unknown)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Strict<T>
+where
+    T: Deserialize<'de> + Known,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Strict<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = T::deserialize(deserializer)?;
+        match value.unknown() {
+            Some(unknown) => Err(D::Error::custom(format!(
+                "unrecognised payload: {}",
+                unknown
+            ))),
+            None => Ok(Strict(value)),
+        }
+    }
+}
+
+/// Reads an internally tagged object, returning the tag and the rest of the object.
+pub(crate) fn tagged<'de, D>(
+    deserializer: D,
+    tag: &'static str,
+) -> Result<(String, Map<String, Value>), D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut object = Map::deserialize(deserializer)?;
+    match object.remove(tag) {
+        Some(Value::String(name)) => Ok((name, object)),
+        Some(other) => Err(D::Error::custom(format!("invalid {}: {}", tag, other))),
+        None => Err(D::Error::missing_field(tag)),
+    }
+}
+
+/// Decodes the body of a modelled variant, keeping the path of the field that failed.
+pub(crate) fn variant<T, E>(body: Map<String, Value>) -> Result<T, E>
+where
+    T: DeserializeOwned,
+    E: Error,
+{
+    serde_path_to_error::deserialize(Value::Object(body)).map_err(|e| {
+        match e.path().to_string().as_str() {
+            "." => E::custom(e.into_inner()),
+            path => E::custom(format!("{}: {}", path, e.inner())),
+        }
+    })
+}
+
+/// Puts the tag back on an object that no variant models.
+pub(crate) fn unknown(tag: &str, name: String, mut body: Map<String, Value>) -> Value {
+    body.insert(tag.to_string(), Value::String(name));
+    Value::Object(body)
+}
+
+#[test]
+fn unknown_payloads() {
+    use crate::payload::ws::{Command, CommandValue, WebsocketRequest};
+    use crate::verbs::verb::Verb;
+
+    let verbs = r#"[
+        {"verb": "say", "text": "hello"},
+        {"verb": "s3:upload", "id": "upload-1", "bucket": "calls"}
+    ]"#;
+    let mut parsed: Vec<Verb> = serde_json::from_str(verbs).unwrap();
+    assert_eq!(parsed[0].name(), "say");
+    assert_eq!(parsed[1].name(), "unknown");
+    assert_eq!(parsed[1].id(), Some("upload-1"));
+    parsed[1].set_id(Some("upload-2".to_string()));
+    let json = serde_json::to_value(&parsed[1]).unwrap();
+    assert_eq!(json["verb"], "s3:upload");
+    assert_eq!(json["id"], "upload-2");
+    assert_eq!(json["bucket"], "calls");
+
+    let message = r#"{"type": "session:transfer", "msgid": "9f6a"}"#;
+    match serde_json::from_str::<WebsocketRequest>(message).unwrap() {
+        WebsocketRequest::Unknown(message) => assert_eq!(message["msgid"], "9f6a"),
+        _ => panic!("expected unknown message"),
+    }
+
+    let command = r#"{"command": "conf:kick", "queueCommand": false}"#;
+    let command: Command = serde_json::from_str(command).unwrap();
+    assert!(matches!(command.command_type, CommandValue::Unknown(_)));
+
+    // Only unknown names fall back; a bad body for a known one is an error.
+    let error = serde_json::from_str::<Vec<Verb>>(r#"[{"verb": "say", "text": 1}]"#)
+        .err()
+        .unwrap();
+    assert!(error.to_string().starts_with("text: invalid type"), "{}", error);
+    assert!(serde_json::from_str::<WebsocketRequest>(r#"{"type": "verb:hook"}"#).is_err());
+    assert!(serde_json::from_str::<Command>(r#"{"command": "redirect"}"#).is_err());
+
+    assert!(serde_json::from_str::<Strict<Vec<Verb>>>(verbs).is_err());
+    assert!(serde_json::from_str::<Strict<WebsocketRequest>>(message).is_err());
+    assert!(serde_json::from_str::<Strict<Command>>(r#"{"command": "conf:kick"}"#).is_err());
+    let say: Strict<Vec<Verb>> =
+        serde_json::from_str(r#"[{"verb": "say", "text": "hi"}]"#).unwrap();
+    assert_eq!(say.into_inner()[0].name(), "say");
+}
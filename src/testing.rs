@@ -0,0 +1,579 @@
+//! Simulates jambonz so that call flows can be tested without placing calls.
+//!
+//! A `Simulator` sends `session:new` to an app, runs the verbs it replies
+//! with one at a time and answers gathers, dials and amd from a script of
+//! `CallerInput`. Apps are driven over the websocket protocol (`Websocket`,
+//! any `SessionHandler`) or as webhooks (`Webhook`, e.g. a `HookRouter`).
+//! `fixtures` records and round trips golden files of jambonz JSON.
+
+pub mod fixtures;
+pub mod scenario;
+
+use crate::payload::rest::{AmdEvent, DialCallStatus, InitialRequest};
+use crate::payload::session::{CallSession, SessionHandler};
+use crate::payload::ws::{Ack, Command, Verbs, WebsocketReply, WebsocketRequest};
+use crate::verbs::verb::Verb;
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+use futures::executor::block_on;
+use futures::stream::{Empty, empty};
+use serde_json::{Map, Value, json};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// What the caller does when the simulator next needs input.
+#[derive(Clone)]
+pub enum CallerInput {
+    /// Presses keys during a gather.
+    Digits(String),
+    /// Speaks during a gather.
+    Speech(String),
+    /// Says nothing, so a gather times out.
+    Silence,
+    /// Hangs up instead of answering a gather or dial.
+    Hangup,
+    /// The answering machine detection result for the next dial with `amd`.
+    Amd(AmdEvent),
+    /// How the next dial ends. Dials are `Completed` unless scripted.
+    Dial(DialCallStatus),
+}
+
+/// Why a `Simulator` stopped before the call ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimulatorError {
+    /// The app failed to handle a frame, e.g. a `CallSession` could not
+    /// encode or send a reply.
+    App(String),
+    /// The app sent a reply that is not a websocket reply.
+    InvalidReply(String),
+    /// The next scripted input cannot be taken by the verb being run, e.g.
+    /// `Dial` for a gather.
+    UnexpectedInput { verb: &'static str, input: String },
+}
+
+impl fmt::Display for SimulatorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulatorError::App(e) => write!(f, "app failed: {}", e),
+            SimulatorError::InvalidReply(reply) => write!(f, "app sent an invalid reply {}", reply),
+            SimulatorError::UnexpectedInput { verb, input } => {
+                write!(f, "{} was scripted {} input", verb, input)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimulatorError {}
+
+/// An app under test, fed the JSON frames jambonz would send.
+pub trait App {
+    /// Returns the replies to `frame`, e.g. an ack with the next verbs.
+    fn deliver(&mut self, frame: &Value) -> Result<Vec<WebsocketReply>, SimulatorError>;
+}
+
+/// Drives a `SessionHandler` through a `CallSession`, as over a websocket.
+pub struct Websocket<H: SessionHandler> {
+    handler: H,
+    session: CallSession<Empty<String>, UnboundedSender<String>>,
+    replies: UnboundedReceiver<String>,
+}
+
+impl<H: SessionHandler> Websocket<H> {
+    pub fn new(handler: H) -> Websocket<H> {
+        let (sink, replies) = unbounded();
+        Websocket {
+            handler,
+            session: CallSession::new(empty(), sink),
+            replies,
+        }
+    }
+
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+}
+
+impl<H: SessionHandler> App for Websocket<H> {
+    fn deliver(&mut self, frame: &Value) -> Result<Vec<WebsocketReply>, SimulatorError> {
+        block_on(self.session.handle(&mut self.handler, &frame.to_string()))
+            .map_err(|e| SimulatorError::App(e.to_string()))?;
+        let mut replies = vec![];
+        while let Ok(reply) = self.replies.try_recv() {
+            match serde_json::from_str(&reply) {
+                Ok(reply) => replies.push(reply),
+                Err(e) => return Err(SimulatorError::InvalidReply(format!("{}: {}", reply, e))),
+            }
+        }
+        Ok(replies)
+    }
+}
+
+/// Drives a webhook app: `handler` gets the hook path and body, and returns
+/// the verbs it would respond with.
+pub struct Webhook<F> {
+    call_hook: String,
+    call_status_hook: String,
+    handler: F,
+}
+
+impl<F> Webhook<F>
+where
+    F: FnMut(&str, &Value) -> Vec<Verb>,
+{
+    pub fn new(call_hook: &str, call_status_hook: &str, handler: F) -> Webhook<F> {
+        Webhook {
+            call_hook: call_hook.to_string(),
+            call_status_hook: call_status_hook.to_string(),
+            handler,
+        }
+    }
+}
+
+impl<F> App for Webhook<F>
+where
+    F: FnMut(&str, &Value) -> Vec<Verb>,
+{
+    fn deliver(&mut self, frame: &Value) -> Result<Vec<WebsocketReply>, SimulatorError> {
+        let msgid = frame["msgid"].as_str().unwrap_or_default();
+        let verbs = match frame["type"].as_str() {
+            Some("session:new") => (self.handler)(&self.call_hook, &frame["data"]),
+            Some("verb:hook") | Some("session:redirect") => {
+                let hook = frame["hook"].as_str().unwrap_or_default();
+                (self.handler)(hook, &frame["data"])
+            }
+            Some("call:status") => {
+                (self.handler)(&self.call_status_hook, &frame["data"]);
+                return Ok(vec![]);
+            }
+            _ => return Ok(vec![]),
+        };
+        Ok(vec![WebsocketReply::Ack(Ack {
+            msgid: msgid.to_string(),
+            verbs: Verbs {
+                msg_id: msgid.to_string(),
+                data: verbs,
+            },
+        })])
+    }
+}
+
+/// A simulated inbound call.
+pub struct Simulator<A: App> {
+    app: A,
+    call: Map<String, Value>,
+    customer_data: Map<String, Value>,
+    input: VecDeque<CallerInput>,
+    answered_by: Vec<usize>,
+    verbs: Vec<Verb>,
+    hooked_by: Vec<usize>,
+    frames: Vec<WebsocketRequest>,
+    commands: Vec<Command>,
+    hung_up: bool,
+    msgid: u32,
+}
+
+impl<A: App> Simulator<A> {
+    /// A call using the details in `assets/initial-request.json`.
+    pub fn new(app: A) -> Simulator<A> {
+        let initial: InitialRequest =
+            serde_json::from_str(include_str!("assets/initial-request.json"))
+                .expect("bundled initial request");
+        let mut simulator = Simulator {
+            app,
+            call: Map::new(),
+            customer_data: Map::new(),
+            input: VecDeque::new(),
+            answered_by: vec![],
+            verbs: vec![],
+            hooked_by: vec![],
+            frames: vec![],
+            commands: vec![],
+            hung_up: false,
+            msgid: 0,
+        };
+        simulator.initial_request(&initial);
+        simulator
+    }
+
+    /// Replaces the call details sent in `session:new` and every hook.
+    /// `customerData.x_cid` starts as the `X-CID` header, as `tag` would set it.
+    pub fn initial_request(&mut self, initial: &InitialRequest) -> &mut Simulator<A> {
+        let Ok(Value::Object(call)) = serde_json::to_value(initial) else {
+            panic!("InitialRequest did not serialize to an object");
+        };
+        let x_cid = initial.sip.headers.x_cid.clone();
+        self.call = call;
+        self.customer_data = Map::from_iter([("x_cid".to_string(), Value::from(x_cid))]);
+        self
+    }
+
+    pub fn from(&mut self, from: &str) -> &mut Simulator<A> {
+        self.call.insert("from".to_string(), Value::from(from));
+        self
+    }
+
+    pub fn to(&mut self, to: &str) -> &mut Simulator<A> {
+        self.call.insert("to".to_string(), Value::from(to));
+        self
+    }
+
+    /// Queues what the caller does the next time input is needed.
+    pub fn input(&mut self, input: CallerInput) -> &mut Simulator<A> {
+        self.input.push_back(input);
+        self
+    }
+
+    /// Places the call and runs verbs until the app runs out of them or
+    /// the call ends, then sends the final `call:status`.
+    ///
+    /// As in jambonz, verbs returned from an action hook replace the rest of
+    /// the current list, while an empty response lets it carry on.
+    ///
+    /// Stops at the first `SimulatorError`, without sending the final
+    /// `call:status`.
+    pub fn run(&mut self) -> Result<&mut Simulator<A>, SimulatorError> {
+        let initial = Value::Object(self.call.clone());
+        let mut queue: VecDeque<Verb> = self.send("session:new", None, initial)?.into();
+        while let Some(verb) = queue.pop_front() {
+            self.verbs.push(verb.clone());
+            let next = match &verb {
+                Verb::Gather(gather) => self.gather(&gather.action_hook)?,
+                Verb::Dial(dial) => self.dial(
+                    dial.amd.as_ref().map(|amd| amd.action_hook.as_str()),
+                    dial.action_hook.as_deref(),
+                )?,
+                Verb::Redirect(redirect) => Some(self.hook(&redirect.action_hook, Map::new())?),
+                Verb::Tag(tag) => {
+                    self.customer_data.extend(
+                        tag.data
+                            .iter()
+                            .map(|(key, value)| (key.clone(), value.clone())),
+                    );
+                    None
+                }
+                Verb::Hangup(_) | Verb::SipDecline(_) => {
+                    self.hung_up = true;
+                    None
+                }
+                _ => None,
+            };
+            if self.hung_up {
+                break;
+            }
+            if let Some(verbs) = next
+                && !verbs.is_empty()
+            {
+                queue = verbs.into();
+            }
+        }
+        let mut status = self.details();
+        status.insert("call_status".to_string(), Value::from("completed"));
+        self.send("call:status", None, Value::Object(status))?;
+        Ok(self)
+    }
+
+    /// Every verb run, in order.
+    pub fn verbs(&self) -> &[Verb] {
+        &self.verbs
+    }
+
+    /// Every frame sent to the app, in order.
+    pub fn frames(&self) -> &[WebsocketRequest] {
+        &self.frames
+    }
+
+    /// Commands the app sent outside of acks.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Whether the call ended with a hangup rather than running out of verbs.
+    pub fn hung_up(&self) -> bool {
+        self.hung_up
+    }
+
+    /// Scripted input that was never needed.
+    pub fn remaining_input(&self) -> usize {
+        self.input.len()
+    }
+
+    /// For each scripted input used, the index in `verbs` of the verb that took it.
+    pub fn answered_by(&self) -> &[usize] {
+        &self.answered_by
+    }
+
+    /// For each `verb:hook` sent, the index in `verbs` of the verb that sent it.
+    pub fn hooked_by(&self) -> &[usize] {
+        &self.hooked_by
+    }
+
+    fn next_input(&mut self) -> Option<CallerInput> {
+        let input = self.input.pop_front()?;
+        self.answered_by.push(self.verbs.len() - 1);
+        Some(input)
+    }
+
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    fn gather(&mut self, action_hook: &str) -> Result<Option<Vec<Verb>>, SimulatorError> {
+        let input = match self.input.front() {
+            Some(CallerInput::Amd(_)) => Some("amd"),
+            Some(CallerInput::Dial(_)) => Some("dial"),
+            _ => None,
+        };
+        if let Some(input) = input {
+            return Err(SimulatorError::UnexpectedInput {
+                verb: "gather",
+                input: input.to_string(),
+            });
+        }
+        let result = match self.next_input() {
+            Some(CallerInput::Digits(digits)) => json!({
+                "reason": "dtmfDetected",
+                "digits": digits,
+            }),
+            Some(CallerInput::Speech(transcript)) => json!({
+                "reason": "speechDetected",
+                "speech": {
+                    "is_final": true,
+                    "alternatives": [{"confidence": 0.9, "transcript": transcript}],
+                },
+            }),
+            Some(CallerInput::Silence) | None => json!({"reason": "timeout"}),
+            Some(CallerInput::Hangup) => {
+                self.hung_up = true;
+                return Ok(None);
+            }
+            Some(CallerInput::Amd(_)) | Some(CallerInput::Dial(_)) => unreachable!(),
+        };
+        let Value::Object(result) = result else {
+            unreachable!()
+        };
+        self.hook(action_hook, result).map(Some)
+    }
+
+    fn dial(
+        &mut self,
+        amd_hook: Option<&str>,
+        action_hook: Option<&str>,
+    ) -> Result<Option<Vec<Verb>>, SimulatorError> {
+        if let Some(amd_hook) = amd_hook
+            && let Some(CallerInput::Amd(event)) = self.input.front().cloned()
+        {
+            self.next_input();
+            if let Ok(Value::Object(event)) = serde_json::to_value(&event) {
+                let verbs = self.hook(amd_hook, event)?;
+                if !verbs.is_empty() {
+                    return Ok(Some(verbs));
+                }
+            }
+        }
+        let status = match self.input.front() {
+            Some(CallerInput::Dial(status)) => {
+                let status = status.clone();
+                self.next_input();
+                status
+            }
+            Some(CallerInput::Hangup) => {
+                self.next_input();
+                self.hung_up = true;
+                return Ok(None);
+            }
+            _ => DialCallStatus::Completed,
+        };
+        let Some(action_hook) = action_hook else {
+            return Ok(None);
+        };
+        let sip_status = match status {
+            DialCallStatus::Completed => 200,
+            DialCallStatus::Busy => 486,
+            DialCallStatus::NoAnswer => 487,
+            DialCallStatus::Failed | DialCallStatus::Unknown => 503,
+        };
+        let outcome = json!({
+            "dial_call_sid": format!("{}-dial", self.call["call_sid"].as_str().unwrap_or_default()),
+            "dial_call_status": status,
+            "dial_sip_status": sip_status,
+            "fs_public_ip": self.call["public_ip"],
+        });
+        let Value::Object(outcome) = outcome else {
+            unreachable!()
+        };
+        self.hook(action_hook, outcome).map(Some)
+    }
+
+    fn hook(&mut self, hook: &str, body: Map<String, Value>) -> Result<Vec<Verb>, SimulatorError> {
+        let mut data = self.details();
+        data.extend(body);
+        self.hooked_by.push(self.verbs.len() - 1);
+        self.send("verb:hook", Some(hook), Value::Object(data))
+    }
+
+    /// The call details jambonz sends with every hook once the call is answered.
+    fn details(&self) -> Map<String, Value> {
+        let mut details = self.call.clone();
+        details.remove("sip");
+        details.remove("defaults");
+        details.insert("call_status".to_string(), Value::from("in-progress"));
+        details.insert("sip_status".to_string(), Value::from(200));
+        details.insert("sip_reason".to_string(), Value::from("OK"));
+        if let Some(address) = self.call.get("local_sip_address") {
+            details.insert("fs_sip_address".to_string(), address.clone());
+        }
+        details.insert(
+            "customerData".to_string(),
+            Value::Object(self.customer_data.clone()),
+        );
+        details
+    }
+
+    /// Sends a frame and returns the verbs the app acked it with.
+    fn send(
+        &mut self,
+        kind: &str,
+        hook: Option<&str>,
+        data: Value,
+    ) -> Result<Vec<Verb>, SimulatorError> {
+        self.msgid += 1;
+        let msgid = format!("msg-{}", self.msgid);
+        let mut frame = json!({
+            "type": kind,
+            "msgid": msgid,
+            "call_sid": self.call["call_sid"],
+            "data": data,
+        });
+        if let Some(hook) = hook {
+            frame["hook"] = Value::from(hook);
+        }
+        match serde_json::from_value(frame.clone()) {
+            Ok(WebsocketRequest::Unknown(_)) | Err(_) => {
+                panic!("simulated frame does not decode: {}", frame)
+            }
+            Ok(request) => self.frames.push(request),
+        }
+
+        let mut verbs = vec![];
+        for reply in self.app.deliver(&frame)? {
+            match reply {
+                WebsocketReply::Ack(ack) if ack.msgid == msgid => verbs = ack.verbs.data,
+                WebsocketReply::Ack(_) | WebsocketReply::SipRequestAck(_) => {}
+                WebsocketReply::Command(command) => self.commands.push(command),
+            }
+        }
+        Ok(verbs)
+    }
+}
+
+#[test]
+fn simulate_ivr() {
+    use crate::payload::rest::{GatherResult, HookKind, Request};
+    use crate::payload::router::HookRouter;
+    use crate::payload::session::SessionContext;
+    use crate::payload::ws::{LlmUpdate, SessionNew, SessionVerbHook};
+    use crate::verbs::dial::Dial;
+    use crate::verbs::enqueue::Enqueue;
+    use crate::verbs::gather::Gather;
+    use crate::verbs::hangup::Hangup;
+    use crate::verbs::say::Say;
+    use crate::verbs::target::Phone;
+
+    let menu = || {
+        let mut gather = Gather::new("/menu");
+        gather.digits().say(Some(Say::new(
+            "Press 1 for sales or 2 for billing".to_string(),
+        )));
+        gather.into()
+    };
+    let mut router = HookRouter::new();
+    router
+        .kind("/", HookKind::Call)
+        .route("/", move |_, _| menu())
+        .route("/menu", |_, request| {
+            let digits = match request {
+                Request::Subsequent(result) => result.digits,
+                _ => None,
+            };
+            match digits.as_deref() {
+                Some("1") => {
+                    let mut dial = Dial::new(
+                        "+441234567890",
+                        Phone::new("+447000000001".to_string()).into(),
+                    );
+                    dial.action_hook = Some("/dial".to_string());
+                    dial.into()
+                }
+                Some("2") => Enqueue::new("billing".to_string()).into(),
+                _ => Hangup::hangup().into(),
+            }
+        })
+        .route("/dial", |_, _| Say::new("Goodbye".to_string()).into());
+
+    let mut call = Simulator::new(Webhook::new("/", "/status", |hook: &str, data: &Value| {
+        router.dispatch(hook, data)
+    }));
+    call.input(CallerInput::Digits("1".to_string()))
+        .input(CallerInput::Dial(DialCallStatus::Busy))
+        .run()
+        .unwrap();
+    let names: Vec<&str> = call.verbs().iter().map(|verb| verb.name()).collect();
+    assert_eq!(names, vec!["gather", "dial", "say"]);
+    assert!(!call.hung_up());
+    assert_eq!(call.remaining_input(), 0);
+    assert!(matches!(
+        call.frames().last(),
+        Some(WebsocketRequest::CallStatus(_))
+    ));
+    let Some(WebsocketRequest::VerbHook(dial)) = call.frames().get(2) else {
+        panic!("expected the dial hook");
+    };
+    assert_eq!(dial.data["dial_call_status"], "busy");
+
+    struct Ivr {
+        transcripts: Vec<String>,
+    }
+
+    impl SessionHandler for Ivr {
+        async fn on_new(&mut self, context: &mut SessionContext, session: SessionNew) -> Vec<Verb> {
+            let mut gather = Gather::new("/menu");
+            gather.speech();
+            gather.into()
+        }
+
+        async fn on_verb_hook(
+            &mut self,
+            context: &mut SessionContext,
+            hook: SessionVerbHook,
+        ) -> Vec<Verb> {
+            let result: GatherResult = hook.data_as().unwrap();
+            self.transcripts
+                .push(result.transcript().unwrap_or_default().to_string());
+            context.send(LlmUpdate::new(json!({"type": "session.update"})));
+            if self.transcripts.len() < 2 {
+                let mut gather = Gather::new("/menu");
+                gather.speech();
+                gather.into()
+            } else {
+                Hangup::hangup().into()
+            }
+        }
+    }
+
+    let mut call = Simulator::new(Websocket::new(Ivr {
+        transcripts: vec![],
+    }));
+    call.from("+447700900123")
+        .input(CallerInput::Speech("billing".to_string()))
+        .input(CallerInput::Silence)
+        .run()
+        .unwrap();
+    assert_eq!(call.app().handler().transcripts, vec!["billing", ""]);
+    let names: Vec<&str> = call.verbs().iter().map(|verb| verb.name()).collect();
+    assert_eq!(names, vec!["gather", "gather", "hangup"]);
+    assert!(call.hung_up());
+    assert_eq!(call.commands().len(), 2);
+    let Some(WebsocketRequest::SessionNew(session)) = call.frames().first() else {
+        panic!("expected session:new");
+    };
+    assert_eq!(session.data.from, "+447700900123");
+}
@@ -0,0 +1,226 @@
+//! Golden files of jambonz JSON. Each fixture is decoded into this crate's
+//! types, encoded again and compared with the original, so a field that is
+//! dropped or renamed on the way through shows up as a difference.
+//!
+//! Record a frame captured from a live jambonz with one call, e.g.
+//! `Fixture::Websocket.record(dir, "llm-event", json)`, and check it with
+//! `Fixture::Websocket.round_trip("llm-event", json)`.
+
+use crate::payload::rest::{HookKind, Request};
+use crate::payload::ws::{SessionRecording, WebsocketRequest};
+use crate::verbs::verb::Verb;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// This crate's own fixtures.
+#[cfg(test)]
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/assets/fixtures");
+
+/// The kinds of JSON jambonz sends or accepts, and the type each decodes into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fixture {
+    /// A single verb, decoded as `Verb`.
+    Verb,
+    /// A websocket text frame, decoded as `WebsocketRequest`.
+    Websocket,
+    /// The first frame on a `listen` websocket, decoded as `SessionRecording`.
+    Recording,
+    /// A webhook body, decoded with `Request::decode` for the `HookKind`
+    /// named by the fixture, see `Fixture::hook_kind`.
+    Hook,
+}
+
+impl Fixture {
+    pub const ALL: [Fixture; 4] = [
+        Fixture::Verb,
+        Fixture::Websocket,
+        Fixture::Recording,
+        Fixture::Hook,
+    ];
+
+    /// The directory under `root` that holds fixtures of this kind.
+    pub fn dir(&self, root: impl AsRef<Path>) -> PathBuf {
+        let name = match self {
+            Fixture::Verb => "verbs",
+            Fixture::Websocket => "ws",
+            Fixture::Recording => "recording",
+            Fixture::Hook => "hooks",
+        };
+        root.as_ref().join(name)
+    }
+
+    /// The hook a `Fixture::Hook` named `name` was posted to: `call`,
+    /// `call-status` and `messaging` name the application hooks, and
+    /// `<verb>-<hook>` a verb hook, e.g. `dial-action` for the dial `actionHook`.
+    pub fn hook_kind(name: &str) -> HookKind {
+        match name {
+            "call" => HookKind::Call,
+            "call-status" => HookKind::CallStatus,
+            "messaging" => HookKind::Messaging,
+            _ => match name.split_once('-') {
+                Some((verb, hook)) => HookKind::verb(verb, &format!("{}Hook", hook)),
+                None => HookKind::Other,
+            },
+        }
+    }
+
+    /// Deserializes `json`, serializes it back and compares the two as JSON.
+    /// Returns one line per difference, or the decode error. `name` is the
+    /// fixture name, which only `Fixture::Hook` reads.
+    pub fn round_trip(&self, name: &str, json: &str) -> Result<(), Vec<String>> {
+        let expected: Value = serde_json::from_str(json).map_err(|e| vec![format!("$: {}", e)])?;
+        let actual = match self {
+            Fixture::Verb => match decode::<Verb>(&expected)? {
+                Verb::Unknown(_) => return Err(vec!["$: not a known verb".to_string()]),
+                verb => encode(&verb)?,
+            },
+            Fixture::Websocket => match decode::<WebsocketRequest>(&expected)? {
+                WebsocketRequest::Unknown(_) => {
+                    return Err(vec!["$: not a known message type".to_string()]);
+                }
+                request => encode(&request)?,
+            },
+            Fixture::Recording => encode(&decode::<SessionRecording>(&expected)?)?,
+            Fixture::Hook => {
+                let request = Request::decode(&Fixture::hook_kind(name), &expected)
+                    .map_err(|e| vec![format!("$: {}", e)])?;
+                encode(&request)?
+            }
+        };
+        let mut differences = vec![];
+        compare(&expected, &actual, "$", &mut differences);
+        if differences.is_empty() {
+            Ok(())
+        } else {
+            Err(differences)
+        }
+    }
+
+    /// Saves `json`, e.g. a frame captured from a live jambonz, as the fixture
+    /// `name` in `self.dir(root)`, pretty printed.
+    pub fn record(&self, root: impl AsRef<Path>, name: &str, json: &str) -> io::Result<PathBuf> {
+        let value: Value = serde_json::from_str(json)?;
+        let dir = self.dir(root);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.json", name));
+        fs::write(&path, serde_json::to_string_pretty(&value)? + "\n")?;
+        Ok(path)
+    }
+}
+
+fn decode<T: DeserializeOwned>(value: &Value) -> Result<T, Vec<String>> {
+    serde_path_to_error::deserialize(value)
+        .map_err(|e| vec![format!("$.{}: {}", e.path(), e.inner())])
+}
+
+fn encode<T: Serialize>(value: &T) -> Result<Value, Vec<String>> {
+    serde_json::to_value(value).map_err(|e| vec![format!("$: {}", e)])
+}
+
+/// Compares semantically: `null` is the same as a missing key, numbers are
+/// compared by value at `f32` precision, the precision most fields are read
+/// at, and a number matches its string form, as ids such as `member_id` are
+/// read from either.
+fn compare(expected: &Value, actual: &Value, path: &str, differences: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = format!("{}.{}", path, key);
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => compare(expected, actual, &path, differences),
+                    (Some(Value::Null), None) | (None, Some(Value::Null)) => {}
+                    (Some(_), None) => differences.push(format!("{}: dropped", path)),
+                    (None, Some(_)) => differences.push(format!("{}: added", path)),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                compare(
+                    expected,
+                    actual,
+                    &format!("{}[{}]", path, index),
+                    differences,
+                );
+            }
+        }
+        (Value::Number(expected), Value::Number(actual)) if same_number(expected, actual) => {}
+        (Value::Number(expected), Value::String(actual)) if expected.to_string() == *actual => {}
+        (expected, actual) if expected == actual => {}
+        (expected, actual) => differences.push(format!(
+            "{}: expected {} but got {}",
+            path, expected, actual
+        )),
+    }
+}
+
+fn same_number(expected: &serde_json::Number, actual: &serde_json::Number) -> bool {
+    match (expected.as_f64(), actual.as_f64()) {
+        (Some(expected), Some(actual)) => {
+            (expected - actual).abs() <= f64::from(f32::EPSILON) * expected.abs().max(1.0)
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn fixtures_round_trip() {
+    let mut failures = vec![];
+    for fixture in Fixture::ALL {
+        let dir = fixture.dir(FIXTURES);
+        let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+            })
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty(), "no fixtures in {:?}", dir);
+        for path in paths {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let json = fs::read_to_string(&path).unwrap();
+            if let Err(differences) = fixture.round_trip(&name, &json) {
+                failures.extend(differences.iter().map(|line| format!("{}: {}", name, line)));
+            }
+        }
+    }
+    assert_eq!(failures, Vec::<String>::new());
+}
+
+#[test]
+fn record_fixture() {
+    let json = r#"{"verb": "pause", "length": 2}"#;
+    let root = std::env::temp_dir().join(format!("cal-jambonz-{}", std::process::id()));
+    let path = Fixture::Verb.record(&root, "pause", json).unwrap();
+    let recorded = fs::read_to_string(&path);
+    fs::remove_dir_all(&root).unwrap();
+    assert_eq!(path, root.join("verbs").join("pause.json"));
+    assert_eq!(
+        serde_json::from_str::<Value>(&recorded.unwrap()).unwrap(),
+        serde_json::from_str::<Value>(json).unwrap()
+    );
+
+    let differences = Fixture::Verb
+        .round_trip(
+            "gather",
+            r#"{"verb": "gather", "actionHook": "/menu", "numDigits": 4, "typo": 1}"#,
+        )
+        .unwrap_err();
+    assert_eq!(differences, vec!["$.typo: dropped"]);
+    assert_eq!(
+        Fixture::Websocket.round_trip("unknown", r#"{"type": "session:unknown"}"#),
+        Err(vec!["$: not a known message type".to_string()])
+    );
+    assert_eq!(Fixture::hook_kind("dial-action"), HookKind::verb("dial", "actionHook"));
+    assert_eq!(Fixture::hook_kind("call-status"), HookKind::CallStatus);
+}
@@ -0,0 +1,460 @@
+use crate::payload::rest::{HookKind, InitialRequest, Request};
+use crate::payload::ws::Verbs;
+use crate::testing::{CallerInput, Simulator, SimulatorError, Webhook};
+use crate::verbs::gather::Input;
+use crate::verbs::verb::Verb;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A scripted call: the verbs a handler is expected to reply with, and what
+/// the caller does in between.
+///
+/// Expectations are matched in order against the verbs the call ran. Verbs
+/// between two expectations are skipped, except a gather, which must be
+/// expected since it waits for the caller. Each reply must be taken by the
+/// verb matched by the expectation just before it.
+pub struct Scenario {
+    initial: Option<InitialRequest>,
+    from: Option<String>,
+    to: Option<String>,
+    steps: Vec<Step>,
+}
+
+enum Step {
+    Expect {
+        description: String,
+        matches: Box<dyn Fn(&Verb) -> bool>,
+    },
+    Input(CallerInput),
+}
+
+/// The first step of a `Scenario` that did not hold, numbered from 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioError {
+    pub step: usize,
+    pub message: String,
+    /// The names of the verbs the call ran.
+    pub verbs: Vec<&'static str>,
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step {}: {} (verbs run: {})",
+            self.step,
+            self.message,
+            self.verbs.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Scenario::new()
+    }
+}
+
+impl Scenario {
+    /// A call using the details in `assets/initial-request.json`.
+    pub fn new() -> Scenario {
+        Scenario {
+            initial: None,
+            from: None,
+            to: None,
+            steps: vec![],
+        }
+    }
+
+    pub fn initial_request(&mut self, initial: InitialRequest) -> &mut Scenario {
+        self.initial = Some(initial);
+        self
+    }
+
+    pub fn from(&mut self, from: &str) -> &mut Scenario {
+        self.from = Some(from.to_string());
+        self
+    }
+
+    pub fn to(&mut self, to: &str) -> &mut Scenario {
+        self.to = Some(to.to_string());
+        self
+    }
+
+    /// Expects the next matching verb to satisfy `matches`.
+    pub fn expect<F>(&mut self, description: &str, matches: F) -> &mut Scenario
+    where
+        F: Fn(&Verb) -> bool + 'static,
+    {
+        self.steps.push(Step::Expect {
+            description: description.to_string(),
+            matches: Box::new(matches),
+        });
+        self
+    }
+
+    /// Expects a verb named `name`, e.g. "hangup" or "sip:decline".
+    pub fn expect_verb(&mut self, name: &'static str) -> &mut Scenario {
+        self.expect(name, move |verb| verb.name() == name)
+    }
+
+    /// Expects a gather listening for `input`. Gathers default to digits.
+    pub fn expect_gather(&mut self, input: Input) -> &mut Scenario {
+        let description = match input {
+            Input::Digits => "gather with digits input",
+            Input::Speech => "gather with speech input",
+        };
+        self.expect(description, move |verb| match verb {
+            Verb::Gather(gather) => match &gather.input {
+                Some(inputs) => inputs.iter().any(|candidate| {
+                    std::mem::discriminant(candidate) == std::mem::discriminant(&input)
+                }),
+                None => matches!(input, Input::Digits),
+            },
+            _ => false,
+        })
+    }
+
+    /// Expects a say, or a gather prompting with a say, whose text contains `text`.
+    pub fn expect_say_containing(&mut self, text: &str) -> &mut Scenario {
+        let text = text.to_string();
+        self.expect(
+            &format!("say containing {:?}", text),
+            move |verb| match verb {
+                Verb::Say(say) => say.text.contains(&text),
+                Verb::Gather(gather) => gather
+                    .say
+                    .as_ref()
+                    .is_some_and(|say| say.text.contains(&text)),
+                _ => false,
+            },
+        )
+    }
+
+    pub fn expect_play(&mut self, url: &str) -> &mut Scenario {
+        let url = url.to_string();
+        self.expect(&format!("play of {}", url), move |verb| match verb {
+            Verb::Play(play) => play.url == url,
+            _ => false,
+        })
+    }
+
+    pub fn expect_enqueue(&mut self, name: &str) -> &mut Scenario {
+        let name = name.to_string();
+        self.expect(&format!("enqueue to {:?}", name), move |verb| match verb {
+            Verb::Enqueue(enqueue) => enqueue.name == name,
+            _ => false,
+        })
+    }
+
+    pub fn expect_hangup(&mut self) -> &mut Scenario {
+        self.expect_verb("hangup")
+    }
+
+    /// Answers the last expected gather (or dial) with `input`.
+    pub fn reply(&mut self, input: CallerInput) -> &mut Scenario {
+        self.steps.push(Step::Input(input));
+        self
+    }
+
+    pub fn digits(&mut self, digits: &str) -> &mut Scenario {
+        self.reply(CallerInput::Digits(digits.to_string()))
+    }
+
+    pub fn speech(&mut self, transcript: &str) -> &mut Scenario {
+        self.reply(CallerInput::Speech(transcript.to_string()))
+    }
+
+    pub fn silence(&mut self) -> &mut Scenario {
+        self.reply(CallerInput::Silence)
+    }
+
+    pub fn hangup(&mut self) -> &mut Scenario {
+        self.reply(CallerInput::Hangup)
+    }
+
+    /// Runs the call against `handler`, which gets every hook but the final
+    /// call status as a `Request`, decoded as the payload of the verb that
+    /// set the hook.
+    pub fn run<H>(&self, mut handler: H) -> Result<(), ScenarioError>
+    where
+        H: FnMut(Request) -> Verbs,
+    {
+        let mut kinds: HashMap<String, HookKind> = HashMap::new();
+        let mut delivered = 0;
+        let mut decode_error = None;
+        let app = Webhook::new("/", "/status", |hook: &str, data: &Value| {
+            if hook == "/status" {
+                return vec![];
+            }
+            let kind = match delivered {
+                0 => HookKind::Call,
+                _ => kinds.get(hook).cloned().unwrap_or_default(),
+            };
+            delivered += 1;
+            match Request::decode(&kind, data) {
+                Ok(request) => {
+                    let verbs = handler(request).data;
+                    kinds.extend(verbs.iter().flat_map(Verb::hooks));
+                    verbs
+                }
+                Err(e) => {
+                    decode_error.get_or_insert((delivered - 1, format!("{}: {}", hook, e)));
+                    vec![]
+                }
+            }
+        });
+        let mut simulator = Simulator::new(app);
+        if let Some(initial) = &self.initial {
+            simulator.initial_request(initial);
+        }
+        if let Some(from) = &self.from {
+            simulator.from(from);
+        }
+        if let Some(to) = &self.to {
+            simulator.to(to);
+        }
+        for step in &self.steps {
+            if let Step::Input(input) = step {
+                simulator.input(input.clone());
+            }
+        }
+        let stopped = simulator.run().err();
+        let verbs = simulator.verbs().to_vec();
+        let answered_by = simulator.answered_by().to_vec();
+        let hooked_by = simulator.hooked_by().to_vec();
+        drop(simulator);
+
+        let names: Vec<&'static str> = verbs.iter().map(|verb| verb.name()).collect();
+        let error = |step: usize, message: String| ScenarioError {
+            step: step + 1,
+            message,
+            verbs: names.clone(),
+        };
+        // An input the simulator could not use fails at that input, and any
+        // other simulator error at the last step.
+        if let Some(stopped) = stopped {
+            let step = match stopped {
+                SimulatorError::UnexpectedInput { .. } => self
+                    .steps
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, step)| matches!(step, Step::Input(_)))
+                    .nth(answered_by.len())
+                    .map(|(index, _)| index),
+                _ => None,
+            };
+            let step = step.unwrap_or(self.steps.len().saturating_sub(1));
+            return Err(error(step, stopped.to_string()));
+        }
+        // The first hook is session:new, sent before any verb ran.
+        let (failed, decode_error) = match decode_error {
+            Some((0, message)) => return Err(error(0, message)),
+            Some((delivery, message)) => (Some(hooked_by[delivery - 1]), Some(message)),
+            None => (None, None),
+        };
+        // A hook set by an expected verb fails at the last step about that verb.
+        let mut pending = false;
+        let mut expected: Option<(usize, &str)> = None;
+        let mut ran = verbs.iter().enumerate();
+        let mut answered = answered_by.iter();
+        for (index, step) in self.steps.iter().enumerate() {
+            let (description, matches) = match step {
+                Step::Expect {
+                    description,
+                    matches,
+                } => (description, matches),
+                Step::Input(_) => {
+                    match (answered.next(), expected) {
+                        (Some(at), Some((matched, _))) if *at == matched => {}
+                        (Some(at), Some((_, description))) => {
+                            return Err(error(
+                                index,
+                                format!(
+                                    "the reply was taken by the {} at verb {}, not the {}",
+                                    names[*at],
+                                    at + 1,
+                                    description
+                                ),
+                            ));
+                        }
+                        (Some(at), None) => {
+                            return Err(error(
+                                index,
+                                format!(
+                                    "the reply was taken by the {} at verb {} before anything was expected",
+                                    names[*at],
+                                    at + 1
+                                ),
+                            ));
+                        }
+                        (None, _) => {
+                            return Err(error(index, "the reply was never needed".to_string()));
+                        }
+                    }
+                    continue;
+                }
+            };
+            if let Some(message) = decode_error.clone().filter(|_| pending) {
+                return Err(error(index - 1, message));
+            }
+            loop {
+                let Some((at, verb)) = ran.next() else {
+                    return Err(error(
+                        index,
+                        format!("expected {} but the call ended", description),
+                    ));
+                };
+                if matches(verb) {
+                    pending = failed == Some(at);
+                    expected = Some((at, description));
+                    break;
+                }
+                if failed == Some(at)
+                    && let Some(message) = decode_error.clone()
+                {
+                    return Err(error(index, message));
+                }
+                if let Verb::Gather(_) = verb {
+                    return Err(error(
+                        index,
+                        format!("expected {} but got an unexpected gather", description),
+                    ));
+                }
+            }
+        }
+        // Otherwise the hook was set by a verb after the last expectation.
+        if let Some(message) = decode_error {
+            return Err(error(self.steps.len() - 1, message));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn scenario() {
+    use crate::payload::builder::VerbTrait;
+    use crate::payload::rest::DialCallStatus;
+    use crate::verbs::dial::Dial;
+    use crate::verbs::enqueue::Enqueue;
+    use crate::verbs::gather::Gather;
+    use crate::verbs::say::Say;
+    use crate::verbs::target::Phone;
+
+    let ivr = |request: Request| {
+        let mut verbs = Verbs::new("");
+        let choice = match request {
+            Request::Initial(_) => {
+                let mut gather = Gather::new("/menu");
+                gather.speech_digits().say(Some(Say::new(
+                    "Say sales or billing, or press 1 or 2".to_string(),
+                )));
+                verbs.gather(gather);
+                return verbs;
+            }
+            Request::Subsequent(request) => request
+                .digits
+                .or(request.speech.and_then(|speech| {
+                    speech
+                        .alternatives
+                        .first()
+                        .map(|alt| alt.transcript.clone())
+                }))
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+        match choice.as_str() {
+            "1" | "sales" => verbs.say_text("Connecting you to sales"),
+            "2" | "billing" => verbs
+                .say_text("Transferring you to billing")
+                .enqueue(Enqueue::new("billing".to_string())),
+            _ => verbs.say_text("Sorry, goodbye").hangup(),
+        };
+        verbs
+    };
+
+    let mut scenario = Scenario::new();
+    scenario
+        .from("+447700900123")
+        .expect_gather(Input::Speech)
+        .digits("2")
+        .expect_say_containing("billing")
+        .expect_enqueue("billing");
+    assert_eq!(scenario.run(ivr), Ok(()));
+
+    let mut scenario = Scenario::new();
+    scenario
+        .expect_gather(Input::Speech)
+        .speech("sales")
+        .expect_enqueue("sales");
+    let error = scenario.run(ivr).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "step 3: expected enqueue to \"sales\" but the call ended (verbs run: gather, say)"
+    );
+
+    let mut scenario = Scenario::new();
+    scenario
+        .expect_say_containing("goodbye")
+        .silence()
+        .expect_hangup();
+    let error = scenario.run(ivr).unwrap_err();
+    assert_eq!(error.step, 1);
+    assert_eq!(error.verbs, vec!["gather", "say", "hangup"]);
+
+    // The reply goes to the gather, not the say expected just before it.
+    let mut scenario = Scenario::new();
+    scenario
+        .expect_gather(Input::Speech)
+        .expect_say_containing("billing")
+        .digits("2");
+    let error = scenario.run(ivr).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "step 3: the reply was taken by the gather at verb 1, not the say containing \"billing\" \
+         (verbs run: gather, say, enqueue)"
+    );
+
+    // Sharing "/next" with the dial makes the gather result decode as a dial outcome.
+    let shared = |request: Request| {
+        let mut verbs = Verbs::new("");
+        if let Request::Initial(_) = request {
+            let mut dial = Dial::new(
+                "+441234567890",
+                Phone::new("+447000000001".to_string()).into(),
+            );
+            dial.action_hook = Some("/next".to_string());
+            verbs.gather(Gather::new("/next")).dial(dial);
+        }
+        verbs
+    };
+    let mut scenario = Scenario::new();
+    scenario
+        .expect_gather(Input::Digits)
+        .digits("1")
+        .expect_verb("dial");
+    let error = scenario.run(shared).unwrap_err();
+    assert_eq!(error.step, 2);
+    assert!(
+        error
+            .message
+            .starts_with("/next: dial actionHook payload is not a"),
+        "{}",
+        error.message
+    );
+    assert_eq!(error.verbs, vec!["gather", "dial"]);
+
+    // A dial outcome cannot answer a gather.
+    let mut scenario = Scenario::new();
+    scenario
+        .expect_gather(Input::Speech)
+        .reply(CallerInput::Dial(DialCallStatus::Busy));
+    let error = scenario.run(ivr).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "step 2: gather was scripted dial input (verbs run: gather)"
+    );
+}
@@ -3,6 +3,7 @@ use crate::verbs::recognizer::Recognizer;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Amd {
  
     #[serde(rename = "actionHook")]
@@ -56,6 +57,7 @@ impl Amd {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Timers {
    
     #[serde(rename = "decisionTimeoutMs")]
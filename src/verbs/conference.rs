@@ -1,9 +1,14 @@
 use serde::{Deserialize, Serialize};
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Conference {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub beep: Option<bool>,
@@ -21,8 +26,9 @@ pub struct Conference {
     pub start_conference_on_enter: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status_hook: Option<String>,
+    #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub status_events: Vec<String>,
+    pub status_events: Vec<ConferenceStatusEvent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wait_hook: Option<String>,
 }
@@ -42,6 +48,7 @@ impl Into<Vec<Verb>> for Conference {
 impl Conference {
     pub fn new(name:String) -> Conference {
         Conference {
+            id: None,
             name,
             beep: None,
             action_hook: None,
@@ -104,18 +111,51 @@ impl Conference {
         self
     }
 
-    pub fn replace_status_events(&mut self, status_events: Vec<String>) -> &mut Conference {
+    pub fn replace_status_events(
+        &mut self,
+        status_events: Vec<ConferenceStatusEvent>,
+    ) -> &mut Conference {
         self.status_events = status_events;
         self
     }
 
-    pub fn add_status_events(&mut self, status_events: Vec<String>) -> &mut Conference {
+    pub fn add_status_events(
+        &mut self,
+        status_events: Vec<ConferenceStatusEvent>,
+    ) -> &mut Conference {
         self.status_events.extend(status_events);
         self
     }
 
-    pub fn add_status_event(&mut self, status_event: String) -> &mut Conference {
+    pub fn add_status_event(&mut self, status_event: ConferenceStatusEvent) -> &mut Conference {
         self.status_events.push(status_event);
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Conference {
+        self.id = id;
+        self
+    }
+}
+
+/// Conference events that can be sent to the `statusHook`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum ConferenceStatusEvent {
+    Start,
+    End,
+    Join,
+    Leave,
+    StartTalking,
+    StopTalking,
+    /// An event added to jambonz after this crate.
+    #[serde(other)]
+    Unknown,
+}
+
+impl Validate for Conference {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "name", &self.name);
+    }
 }
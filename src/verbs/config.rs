@@ -1,15 +1,22 @@
+use crate::verbs::amd::Amd;
 use crate::verbs::bargein::BargeIn;
 use crate::verbs::listen::Listen;
 use crate::verbs::recognizer::Recognizer;
 use crate::verbs::siprec::SipRec;
 use crate::verbs::synthesizer::Synthesizer;
+use crate::verbs::validate::{Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub amd: Option<bool>,
+    pub id: Option<String>,
+
+    /// Answering machine detection for the rest of the call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amd: Option<Amd>,
 
     #[serde(rename = "bargeIn")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -57,6 +64,7 @@ pub struct Config {
 impl Config {
     pub fn new() -> Config {
         Config {
+            id: None,
             amd: None,
             barge_in: None,
             boost_audio_signal: None,
@@ -73,7 +81,7 @@ impl Config {
         }
     }
 
-    pub fn amd(&mut self, amd: Option<bool>) -> &mut Config {
+    pub fn amd(&mut self, amd: Option<Amd>) -> &mut Config {
         self.amd = amd;
         self
     }
@@ -140,6 +148,11 @@ impl Config {
         self.sip_request_within_dialog_hook = sip_request_within_dialog_hook;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Config {
+        self.id = id;
+        self
+    }
 }
 impl Into<Verb> for Config {
     fn into(self) -> Verb {
@@ -154,6 +167,7 @@ impl Into<Vec<Verb>> for Config {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TranscribeConfig {
     pub enable: bool,
 
@@ -180,7 +194,9 @@ impl TranscribeConfig {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FillerNoise {
+    #[serde(rename = "enable", alias = "enabled")]
     pub enabled: bool,
 
     pub url: String,
@@ -203,3 +219,16 @@ impl FillerNoise {
         self
     }
 }
+
+impl Validate for Config {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        for (index, key) in self.reset.iter().flatten().enumerate() {
+            if !["recognizer", "synthesizer"].contains(&key.as_str()) {
+                errors.push(ValidationError::new(
+                    format!("{}.reset[{}]", path, index),
+                    "must be \"recognizer\" or \"synthesizer\"",
+                ));
+            }
+        }
+    }
+}
@@ -1,9 +1,13 @@
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
     #[serde(rename_all = "camelCase")]
     pub struct Dequeue {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub id: Option<String>,
       
         pub name: String,
      
@@ -32,6 +36,7 @@ use serde::{Deserialize, Serialize};
     impl Dequeue {
         pub fn new(name: String) -> Dequeue {
             Dequeue {
+                id: None,
                 name: name,
                 action_hook: None,
                 beep: None,
@@ -53,5 +58,15 @@ use serde::{Deserialize, Serialize};
             self.timeout = timeout;
             self
         }
+
+        pub fn id(&mut self, id: Option<String>) -> &mut Dequeue {
+            self.id = id;
+            self
+        }
     }
 
+impl Validate for Dequeue {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "name", &self.name);
+    }
+}
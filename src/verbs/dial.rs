@@ -3,14 +3,19 @@ use crate::verbs::constants::{DEFAULT_CALL_TIME, DEFAULT_RING_TIME, HANG_UP_CONN
 use crate::verbs::dub::DubData;
 use crate::verbs::recognizer::Recognizer;
 use crate::verbs::target::Target;
+use crate::verbs::validate::{Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::listen::Listen;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Dial {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     /// Array of up to 10 destinations to simultaneously dial.
     /// The first person (or entity) to answer the call will
     /// be connected to the caller, and the rest of the called
@@ -86,7 +91,7 @@ pub struct Dial {
 
     /// An object containing arbitrary SIP headers
     /// to apply to the outbound call attempt(s).
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
 
     /// A nested listen action, which will cause audio from the call
@@ -121,10 +126,10 @@ pub struct Dial {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exit_media_path: Option<bool>,
 
-    /// A nested dub verb to add additional audio
+    /// Nested dub verbs to add additional audio
     /// tracks into the outbound call.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub dub: Option<DubData>,
+    pub dub: Option<Vec<DubData>>,
 
     /// If true, jambonz will not release the media
     /// from feature server for the bridged call
@@ -149,6 +154,7 @@ impl Into<Vec<Verb>> for Dial {
 impl Dial {
     pub fn new(caller_id: &str, targets: Vec<Target>) -> Dial {
         Dial {
+            id: None,
             action_hook: Some(HANG_UP_CONNECT.to_string()),
             caller_id: Some(caller_id.to_string()),
             dial_music: Some(RING_TONE.to_string()),
@@ -271,8 +277,14 @@ impl Dial {
         self
     }
 
+    /// Replaces the dub tracks with `dub`, or removes them.
     pub fn dub(&mut self, dub: Option<DubData>) -> &mut Dial {
-        self.dub = dub;
+        self.dub = dub.map(|dub| vec![dub]);
+        self
+    }
+
+    pub fn add_dub(&mut self, dub: DubData) -> &mut Dial {
+        self.dub.get_or_insert_with(Vec::new).push(dub);
         self
     }
 
@@ -280,13 +292,18 @@ impl Dial {
         self.anchor_media = anchor_media;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Dial {
+        self.id = id;
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TranscribeDial {
     #[serde(rename = "transcriptionHook")]
     pub transcription_hook: String,
-    #[serde(flatten)]
     pub recognizer: Recognizer,
 }
 
@@ -298,3 +315,22 @@ impl TranscribeDial {
         }
     }
 }
+
+impl Validate for Dial {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        match self.target.len() {
+            0 => errors.push(ValidationError::new(
+                format!("{}.target", path),
+                "must have at least one target",
+            )),
+            1..=10 => {}
+            _ => errors.push(ValidationError::new(
+                format!("{}.target", path),
+                "must not have more than 10 targets",
+            )),
+        }
+        for (index, target) in self.target.iter().enumerate() {
+            target.validate_at(&format!("{}.target[{}]", path, index), errors);
+        }
+    }
+}
@@ -1,10 +1,15 @@
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DialogFlow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub credentials: String,
 
     pub lang: String,
@@ -15,7 +20,8 @@ pub struct DialogFlow {
     pub action_hook: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub baregin: Option<bool>,
+    #[serde(alias = "baregin")]
+    pub bargein: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub event_hook: Option<String>,
@@ -27,7 +33,7 @@ pub struct DialogFlow {
     pub no_input_timeout: Option<u8>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub pass_dtmf_as_text_input: Option<String>,
+    pub pass_dtmf_as_text_input: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thinking_music: Option<String>,
@@ -57,12 +63,13 @@ impl Into<Vec<Verb>> for DialogFlow {
 impl DialogFlow {
     pub fn new(project: String, lang: String, credentials: String) -> DialogFlow {
         DialogFlow {
+            id: None,
             credentials,
             lang,
             project,
             tts: None,
             action_hook: None,
-            baregin: None,
+            bargein: None,
             event_hook: None,
             no_input_event: None,
             no_input_timeout: None,
@@ -78,11 +85,16 @@ impl DialogFlow {
         self
     }
 
-    pub fn baregin(&mut self, baregin: Option<bool>) -> &mut DialogFlow {
-        self.baregin = baregin;
+    pub fn bargein(&mut self, bargein: Option<bool>) -> &mut DialogFlow {
+        self.bargein = bargein;
         self
     }
 
+    #[deprecated(note = "misspelt, use bargein")]
+    pub fn baregin(&mut self, baregin: Option<bool>) -> &mut DialogFlow {
+        self.bargein(baregin)
+    }
+
     pub fn event_hook(&mut self, event_hook: Option<String>) -> &mut DialogFlow {
         self.event_hook = event_hook;
         self
@@ -98,8 +110,17 @@ impl DialogFlow {
         self
     }
 
+    pub fn pass_dtmf_as_text_input(
+        &mut self,
+        pass_dtmf_as_text_input: Option<bool>,
+    ) -> &mut DialogFlow {
+        self.pass_dtmf_as_text_input = pass_dtmf_as_text_input;
+        self
+    }
+
+    #[deprecated(note = "use pass_dtmf_as_text_input, which takes a bool")]
     pub fn pass_dtmf(&mut self, pass_dtmf: Option<String>) -> &mut DialogFlow {
-        self.pass_dtmf_as_text_input = pass_dtmf;
+        self.pass_dtmf_as_text_input = pass_dtmf.and_then(|pass_dtmf| pass_dtmf.parse().ok());
         self
     }
 
@@ -125,9 +146,15 @@ impl DialogFlow {
         self.tts = tts;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut DialogFlow {
+        self.id = id;
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DialogFlowSynthesizer {
     pub language: String,
@@ -167,3 +194,5 @@ impl DialogFlowSynthesizer {
         self
     }
 }
+
+impl Validate for DialogFlow {}
@@ -1,8 +1,13 @@
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Dtmf {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub dtmf: String,
     pub duration: Option<u8>,
 }
@@ -10,6 +15,7 @@ pub struct Dtmf {
 impl Dtmf {
     pub fn new(dtmf: String) -> Dtmf {
         Dtmf {
+            id: None,
             dtmf: dtmf.to_string(),
             duration: None,
         }
@@ -19,6 +25,11 @@ impl Dtmf {
         self.duration = duration;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Dtmf {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for Dtmf {
@@ -32,3 +43,15 @@ impl Into<Vec<Verb>> for Dtmf {
         vec![self.into()]
     }
 }
+
+impl Validate for Dtmf {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "dtmf", &self.dtmf);
+        if !self.dtmf.chars().all(|c| "0123456789*#ABCDwW".contains(c)) {
+            errors.push(ValidationError::new(
+                format!("{}.dtmf", path),
+                "may only contain 0-9, *, #, A-D, w and W",
+            ));
+        }
+    }
+}
@@ -1,7 +1,9 @@
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Dub {
     #[serde(rename = "queueCommand")]
     pub queue_command: bool,
@@ -32,8 +34,15 @@ impl Dub {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DubData {
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Always "dub". Sent as the verb tag, and left out when nested in a dial.
+    #[serde(skip_serializing, default = "dub")]
+    #[cfg_attr(feature = "schema", schemars(skip))]
     pub verb: String,
 
     pub action: DubTrack,
@@ -46,8 +55,10 @@ pub struct DubData {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub say: Option<String>,
 
+    /// Loops the audio of `playOnTrack`.
+    #[serde(rename = "loop")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub loop_count: Option<u8>,
+    pub dub_loop: Option<bool>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gain: Option<String>,
@@ -56,12 +67,13 @@ pub struct DubData {
 impl DubData {
     pub fn new(action: DubTrack, track: String) -> DubData {
         DubData {
+            id: None,
             action,
             verb: "dub".to_string(),
             track,
             play: None,
             say: None,
-            loop_count: None,
+            dub_loop: None,
             gain: None,
         }
     }
@@ -76,8 +88,15 @@ impl DubData {
         self
     }
 
+    pub fn dub_loop(&mut self, dub_loop: Option<bool>) -> &mut DubData {
+        self.dub_loop = dub_loop;
+        self
+    }
+
+    /// jambonz can only loop a track or play it once.
+    #[deprecated(note = "use dub_loop, jambonz takes a bool")]
     pub fn loop_count(&mut self, loop_count: Option<u8>) -> &mut DubData {
-        self.loop_count = loop_count;
+        self.dub_loop = loop_count.map(|count| count != 1);
         self
     }
 
@@ -85,6 +104,11 @@ impl DubData {
         self.gain = gain;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut DubData {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for DubData {
@@ -100,7 +124,12 @@ impl Into<Vec<Verb>> for DubData {
 }
 
 
+fn dub() -> String {
+    "dub".to_string()
+}
+
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum DubTrack {
     AddTrack,
@@ -109,3 +138,5 @@ pub enum DubTrack {
     PlayOnTrack,
     SayOnTrack,
 }
+
+impl Validate for DubData {}
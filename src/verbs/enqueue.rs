@@ -1,9 +1,14 @@
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Enqueue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub name: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -19,6 +24,7 @@ pub struct Enqueue {
 impl Enqueue {
     pub fn new(name: String) -> Enqueue {
         Enqueue {
+            id: None,
             name,
             priority: None,
             action_hook: None,
@@ -40,7 +46,11 @@ impl Enqueue {
         self.wait_hook = hook;
         self
     }
-    
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Enqueue {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for Enqueue {
@@ -54,3 +64,9 @@ impl Into<Vec<Verb>> for Enqueue {
         vec![self.into()]
     }
 }
+
+impl Validate for Enqueue {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "name", &self.name);
+    }
+}
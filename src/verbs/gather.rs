@@ -1,12 +1,17 @@
 use crate::verbs::play::Play;
 use crate::verbs::recognizer::Recognizer;
 use crate::verbs::say::Say;
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Gather {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub action_hook: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -53,11 +58,16 @@ pub struct Gather {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recognizer: Option<Recognizer>,
+
+    /// Seconds of silence before the gather ends with no input.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u8>,
 }
 
 impl Gather {
     pub fn new(action_hook: &str) -> Self {
         Gather {
+            id: None,
             action_hook: action_hook.to_string(),
             action_hook_delay_action: None,
             bargein: None,
@@ -74,6 +84,7 @@ impl Gather {
             play: None,
             say: None,
             recognizer: None,
+            timeout: None,
         }
     }
 
@@ -151,7 +162,7 @@ impl Gather {
     }
 
     pub fn num_digits(&mut self, digits: Option<u8>) -> &mut Gather {
-        self.max_digits = digits;
+        self.num_digits = digits;
         self
     }
     
@@ -169,10 +180,20 @@ impl Gather {
         self.say = say;
         self
     }
+
+    pub fn timeout(&mut self, timeout: Option<u8>) -> &mut Gather {
+        self.timeout = timeout;
+        self
+    }
     
     pub fn build(&mut self) -> Gather {
         self.clone().into()
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Gather {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for Gather {
@@ -188,6 +209,7 @@ impl Into<Vec<Verb>> for Gather {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct ActionHookDelayAction {
     actions: Vec<Verb>,
@@ -198,8 +220,37 @@ pub struct ActionHookDelayAction {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum Input {
     Digits,
     Speech
 }
+
+impl Validate for Gather {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "actionHook", &self.action_hook);
+        // jambonz gathers digits when input is left out, so only an empty
+        // list is an error.
+        if self.input.as_ref().is_some_and(|input| input.is_empty()) {
+            errors.push(ValidationError::new(
+                format!("{}.input", path),
+                "must include \"digits\" or \"speech\"",
+            ));
+        }
+        if let (Some(min_digits), Some(max_digits)) = (self.min_digits, self.max_digits)
+            && min_digits > max_digits
+        {
+            errors.push(ValidationError::new(
+                format!("{}.minDigits", path),
+                "must not be greater than maxDigits",
+            ));
+        }
+        if let Some(say) = &self.say {
+            say.validate_at(&format!("{}.say", path), errors);
+        }
+        if let Some(play) = &self.play {
+            play.validate_at(&format!("{}.play", path), errors);
+        }
+    }
+}
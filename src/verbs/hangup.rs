@@ -1,11 +1,16 @@
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Hangup {
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
 }
 
@@ -24,6 +29,7 @@ impl Into<Vec<Verb>> for Hangup {
 impl Hangup {
     pub fn hangup() -> Hangup {
         Hangup {
+            id: None,
             headers: HashMap::new(),
         }
     }
@@ -31,7 +37,7 @@ impl Hangup {
     pub fn hangup_with_reason(x_reason: String) -> Hangup {
         let mut map = HashMap::new();
         map.insert("X-Reason".to_string(), x_reason);
-        Hangup { headers: map }
+        Hangup { id: None, headers: map }
     }
 
     pub fn reason(&mut self, str: String) -> &mut Hangup {
@@ -53,4 +59,11 @@ impl Hangup {
         self.headers = headers;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Hangup {
+        self.id = id;
+        self
+    }
 }
+
+impl Validate for Hangup {}
@@ -1,12 +1,22 @@
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
-pub struct Leave {}
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Leave {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
 
 impl Leave {
     pub fn new() -> Leave {
-        Leave {}
+        Leave { id: None }
+    }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Leave {
+        self.id = id;
+        self
     }
 }
 
@@ -21,3 +31,5 @@ impl Into<Vec<Verb>> for Leave {
         vec![self.into()]
     }
 }
+
+impl Validate for Leave {}
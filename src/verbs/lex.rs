@@ -1,11 +1,16 @@
 use crate::verbs::synthesizer::Synthesizer;
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Lex {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub bot_id: String,
 
     pub bot_alias: String,
@@ -20,7 +25,7 @@ pub struct Lex {
     pub event_hook: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub intent: Option<String>,
+    pub intent: Option<LexIntent>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub welcome_message: Option<String>,
@@ -44,6 +49,7 @@ impl Lex {
         region: String,
     ) -> Lex {
         Lex {
+            id: None,
             bot_id,
             bot_alias,
             region,
@@ -86,8 +92,9 @@ impl Lex {
         self
     }
 
+    /// Starts the conversation with the intent named `intent`.
     pub fn intent(&mut self, intent: Option<String>) -> &mut Lex {
-        self.intent = intent;
+        self.intent = intent.map(LexIntent::new);
         self
     }
 
@@ -110,6 +117,11 @@ impl Lex {
         self.metadata = metadata;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Lex {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for Lex {
@@ -124,7 +136,29 @@ impl Into<Vec<Verb>> for Lex {
     }
 }
 
+/// The intent a lex conversation starts with, and its slot values.
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LexIntent {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<HashMap<String, String>>,
+}
+
+impl LexIntent {
+    pub fn new(name: String) -> LexIntent {
+        LexIntent { name, slots: None }
+    }
+
+    pub fn slots(&mut self, slots: HashMap<String, String>) -> &mut LexIntent {
+        self.slots = Some(slots);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LexMeta {
     pub slots: Option<HashMap<String, String>>,
     pub context: Option<HashMap<String, String>>,
@@ -150,6 +184,7 @@ impl LexMeta {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum LexLocale {
     #[serde(rename = "en_AU")]
     EnglishAU,
@@ -170,6 +205,7 @@ pub enum LexLocale {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct LexAWSCredentials {
     pub access_key: String,
@@ -184,3 +220,5 @@ impl LexAWSCredentials {
         }
     }
 }
+
+impl Validate for Lex {}
@@ -1,5 +1,6 @@
 use crate::verbs::auth::WSAuth;
 use crate::verbs::transcribe::Transcribe;
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -7,8 +8,12 @@ use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Listen {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub url: String,
 
     pub action_hook: String,
@@ -47,6 +52,7 @@ pub struct Listen {
 impl Listen {
     pub fn new(url: String, action_hook: String) -> Listen {
         Listen {
+            id: None,
             url: url.to_string(),
             action_hook: action_hook.to_string(),
             sample_rate: Some(SampleRate::SR8000),
@@ -106,7 +112,11 @@ impl Listen {
         self.ws_auth = ws_auth;
         self
     }
-    
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Listen {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for Listen {
@@ -131,6 +141,20 @@ pub enum SampleRate {
     SR64000 = 64000,
 }
 
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for SampleRate {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "SampleRate".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "integer",
+            "enum": [8000, 16000, 24000, 48000, 64000]
+        })
+    }
+}
+
 // SampleRate Debug Implementation
 impl Debug for SampleRate {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -146,6 +170,7 @@ impl Debug for SampleRate {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum MixType {
     Mono,
@@ -164,3 +189,10 @@ impl Debug for MixType {
         }
     }
 }
+
+impl Validate for Listen {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "url", &self.url);
+        require(errors, path, "actionHook", &self.action_hook);
+    }
+}
@@ -0,0 +1,295 @@
+use crate::verbs::validate::Validate;
+use crate::verbs::verb::Verb;
+use crate::verbs::vendors::deepgram::DeepgramLlm;
+use crate::verbs::vendors::elevenlabs::ElevenlabsLlm;
+use crate::verbs::vendors::openai::OpenaiLlm;
+use crate::verbs::vendors::ultravox::UltravoxLlm;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Connects the call to a speech-to-speech (realtime) LLM.
+///
+/// The vendor determines the shape of `model`, `auth` and `llmOptions`,
+/// which are flattened into the verb alongside the `vendor` tag.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Llm {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(flatten)]
+    pub vendor: LlmVendor,
+
+    /// Webhook invoked when the llm verb completes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_hook: Option<String>,
+
+    /// Webhook that receives the vendor events listed in `events`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_hook: Option<String>,
+
+    /// Webhook invoked when the model requests a function call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_hook: Option<String>,
+
+    /// Vendor event names (wildcards allowed, e.g. "conversation.item.*")
+    /// that should be sent to the `eventHook`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+}
+
+impl Llm {
+    pub fn new(vendor: LlmVendor) -> Llm {
+        Llm {
+            id: None,
+            vendor,
+            action_hook: None,
+            event_hook: None,
+            tool_hook: None,
+            events: vec![],
+        }
+    }
+
+    pub fn openai(model: String, api_key: String) -> Llm {
+        Llm::new(LlmVendor::Openai(OpenaiLlm::new(model, api_key)))
+    }
+
+    pub fn deepgram(model: String, api_key: String) -> Llm {
+        Llm::new(LlmVendor::Deepgram(DeepgramLlm::new(model, api_key)))
+    }
+
+    pub fn ultravox(model: String, api_key: String) -> Llm {
+        Llm::new(LlmVendor::Ultravox(UltravoxLlm::new(model, api_key)))
+    }
+
+    pub fn elevenlabs(agent_id: String, api_key: Option<String>) -> Llm {
+        Llm::new(LlmVendor::Elevenlabs(ElevenlabsLlm::new(agent_id, api_key)))
+    }
+
+    pub fn vendor(&mut self, vendor: LlmVendor) -> &mut Llm {
+        self.vendor = vendor;
+        self
+    }
+
+    pub fn action_hook(&mut self, action_hook: Option<String>) -> &mut Llm {
+        self.action_hook = action_hook;
+        self
+    }
+
+    pub fn event_hook(&mut self, event_hook: Option<String>) -> &mut Llm {
+        self.event_hook = event_hook;
+        self
+    }
+
+    pub fn tool_hook(&mut self, tool_hook: Option<String>) -> &mut Llm {
+        self.tool_hook = tool_hook;
+        self
+    }
+
+    pub fn replace_events(&mut self, events: Vec<String>) -> &mut Llm {
+        self.events = events;
+        self
+    }
+
+    pub fn add_event(&mut self, event: String) -> &mut Llm {
+        self.events.push(event);
+        self
+    }
+
+    pub fn build(&mut self) -> Llm {
+        self.clone()
+    }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Llm {
+        self.id = id;
+        self
+    }
+}
+
+impl Into<Verb> for Llm {
+    fn into(self) -> Verb {
+        Verb::Llm(self)
+    }
+}
+
+impl Into<Vec<Verb>> for Llm {
+    fn into(self) -> Vec<Verb> {
+        vec![self.into()]
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "vendor")]
+pub enum LlmVendor {
+    Openai(OpenaiLlm),
+    Deepgram(DeepgramLlm),
+    Ultravox(UltravoxLlm),
+    Elevenlabs(ElevenlabsLlm),
+}
+
+/// Api key credentials shared by the OpenAI, Deepgram and Ultravox vendors.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct LlmAuth {
+    pub api_key: String,
+}
+
+impl LlmAuth {
+    pub fn new(api_key: String) -> LlmAuth {
+        LlmAuth { api_key }
+    }
+}
+
+/// A function the model may call, described with a JSON schema.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LlmTool {
+    #[serde(rename = "type")]
+    pub tool_type: String,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    pub parameters: Value,
+}
+
+impl LlmTool {
+    pub fn function(name: String, parameters: Value) -> LlmTool {
+        LlmTool {
+            tool_type: "function".to_string(),
+            name,
+            description: None,
+            parameters,
+        }
+    }
+
+    pub fn description(&mut self, description: Option<String>) -> &mut LlmTool {
+        self.description = description;
+        self
+    }
+}
+
+impl Validate for Llm {}
+
+#[test]
+fn llm() {
+    use crate::payload::builder::VerbTrait;
+    use crate::payload::ws::{Verbs, WebsocketReply};
+    use crate::verbs::vendors::openai::OpenaiResponseCreate;
+
+    let mut openai = OpenaiLlm::new("gpt-4o-realtime-preview".to_string(), "sk-123".to_string());
+    openai.response_create(Some(OpenaiResponseCreate::new(
+        "You are a helpful agent".to_string(),
+    )));
+
+    let llm_cmd: WebsocketReply = Verbs::new("1234")
+        .llm(
+            Llm::new(LlmVendor::Openai(openai))
+                .action_hook(Some("/final".to_string()))
+                .tool_hook(Some("/tool-call".to_string()))
+                .add_event("conversation.item.*".to_string())
+                .build(),
+        )
+        .as_ack_reply();
+
+    let value: serde_json::Value = serde_json::from_str(&llm_cmd.json()).unwrap();
+    let verb = &value["data"][0];
+    assert_eq!(verb["verb"], "llm");
+    assert_eq!(verb["vendor"], "openai");
+    assert_eq!(verb["model"], "gpt-4o-realtime-preview");
+    assert_eq!(verb["auth"]["apiKey"], "sk-123");
+    assert_eq!(verb["toolHook"], "/tool-call");
+    assert_eq!(verb["llmOptions"]["response_create"]["modalities"][1], "audio");
+
+    let parsed: Verb = serde_json::from_value(verb.clone()).unwrap();
+    assert!(matches!(parsed, Verb::Llm(Llm { vendor: LlmVendor::Openai(_), .. })));
+}
+
+#[test]
+fn llm_vendors() {
+    use crate::payload::builder::VerbTrait;
+    use crate::payload::ws::Verbs;
+    use crate::verbs::vendors::deepgram::{DeepgramAgent, DeepgramThink};
+    use crate::verbs::vendors::elevenlabs::ElevenlabsLlmOptions;
+    use crate::verbs::vendors::openai::OpenaiSessionUpdate;
+    use crate::verbs::vendors::ultravox::{UltravoxFirstSpeaker, UltravoxLlmOptions};
+
+    let mut openai = Llm::openai("gpt-4o-realtime-preview".to_string(), "sk-123".to_string());
+    if let LlmVendor::Openai(vendor) = &mut openai.vendor {
+        let mut session = OpenaiSessionUpdate::new();
+        session.max_response_output_tokens(Some(512));
+        vendor.session_update(Some(session));
+    }
+
+    let mut deepgram = Llm::deepgram("voice-agent".to_string(), "dg-123".to_string());
+    if let LlmVendor::Deepgram(vendor) = &mut deepgram.vendor {
+        let mut think = DeepgramThink::new("open_ai".to_string(), "gpt-4o-mini".to_string());
+        think.instructions(Some("Be brief".to_string()));
+        let mut agent = DeepgramAgent::new();
+        agent
+            .listen(Some("nova-2".to_string()))
+            .think(Some(think))
+            .speak(Some("aura-asteria-en".to_string()));
+        vendor.agent(agent);
+    }
+
+    let mut ultravox = Llm::ultravox("fixie-ai/ultravox".to_string(), "uv-123".to_string());
+    if let LlmVendor::Ultravox(vendor) = &mut ultravox.vendor {
+        let mut options = UltravoxLlmOptions::new();
+        options
+            .system_prompt(Some("You are a receptionist".to_string()))
+            .first_speaker(Some(UltravoxFirstSpeaker::Agent));
+        vendor.llm_options(options);
+    }
+
+    let mut elevenlabs = Llm::elevenlabs("agent-1".to_string(), None);
+    if let LlmVendor::Elevenlabs(vendor) = &mut elevenlabs.vendor {
+        let mut options = ElevenlabsLlmOptions::new();
+        options.input_sample_rate(Some(16000));
+        vendor.llm_options(Some(options));
+    }
+
+    let mut verbs = Verbs::new("1234");
+    verbs.llm(openai).llm(deepgram).llm(ultravox).llm(elevenlabs);
+    let value = serde_json::to_value(&verbs.data).unwrap();
+
+    assert_eq!(
+        value[0]["llmOptions"]["session_update"]["max_response_output_tokens"],
+        512
+    );
+
+    assert_eq!(value[1]["vendor"], "deepgram");
+    assert_eq!(value[1]["auth"]["apiKey"], "dg-123");
+    let settings = &value[1]["llmOptions"]["settingsConfiguration"];
+    assert_eq!(settings["type"], "SettingsConfiguration");
+    assert_eq!(settings["agent"]["listen"]["model"], "nova-2");
+    assert_eq!(settings["agent"]["think"]["provider"]["type"], "open_ai");
+    assert_eq!(settings["agent"]["think"]["instructions"], "Be brief");
+    assert_eq!(settings["agent"]["speak"]["model"], "aura-asteria-en");
+
+    assert_eq!(value[2]["vendor"], "ultravox");
+    assert_eq!(value[2]["model"], "fixie-ai/ultravox");
+    assert_eq!(
+        value[2]["llmOptions"]["systemPrompt"],
+        "You are a receptionist"
+    );
+    assert_eq!(value[2]["llmOptions"]["firstSpeaker"], "FIRST_SPEAKER_AGENT");
+
+    assert_eq!(value[3]["vendor"], "elevenlabs");
+    assert_eq!(value[3]["auth"]["agent_id"], "agent-1");
+    assert!(value[3]["auth"].get("api_key").is_none());
+    assert!(value[3].get("model").is_none());
+    assert_eq!(value[3]["llmOptions"]["input_sample_rate"], 16000);
+
+    for verb in value.as_array().unwrap() {
+        let parsed: Verb = serde_json::from_value(verb.clone()).unwrap();
+        assert!(matches!(parsed, Verb::Llm(_)));
+    }
+}
@@ -1,9 +1,14 @@
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Message {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub from: String,
 
     pub to: String,
@@ -20,6 +25,7 @@ pub struct Message {
 impl Message {
     pub fn new(from: String, to: String, text: String) -> Message {
         Message {
+            id: None,
             from,
             to,
             text,
@@ -42,7 +48,11 @@ impl Message {
         self.action_hook = action_hook;
         self
     }
-    
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Message {
+        self.id = id;
+        self
+    }
 }
 impl Into<Verb> for Message {
     fn into(self) -> Verb {
@@ -55,3 +65,11 @@ impl Into<Vec<Verb>> for Message {
         vec![self.into()]
     }
 }
+
+impl Validate for Message {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "from", &self.from);
+        require(errors, path, "to", &self.to);
+        require(errors, path, "text", &self.text);
+    }
+}
@@ -11,6 +11,7 @@ pub mod hangup;
 pub mod leave;
 pub mod lex;
 pub mod listen;
+pub mod llm;
 pub mod redirect;
 pub mod recognizer;
 pub mod rasa;
@@ -32,4 +33,6 @@ pub mod say;
 pub mod auth;
 pub mod vendors;
 pub mod play_say;
-
+pub mod validate;
+#[cfg(feature = "schema")]
+pub mod schema;
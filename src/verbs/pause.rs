@@ -1,15 +1,25 @@
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Pause {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub length: u8,
 }
 
 impl Pause {
     pub fn new(length: u8) -> Pause {
-        Pause { length }
+        Pause { id: None, length }
+    }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Pause {
+        self.id = id;
+        self
     }
 }
 
@@ -24,3 +34,5 @@ impl Into<Vec<Verb>> for Pause {
         vec![self.into()]
     }
 }
+
+impl Validate for Pause {}
@@ -1,9 +1,13 @@
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Play {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
    
     pub url: String,
    
@@ -39,6 +43,7 @@ impl Into<Vec<Verb>> for Play {
 impl Play {
     pub fn new(url: String) -> Self {
         Play {
+            id: None,
             url,
             action_hook: None,
             play_loop: None,
@@ -72,6 +77,15 @@ impl Play {
         self.seek_offset = seek_offset;
         self
     }
-    
-    
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Play {
+        self.id = id;
+        self
+    }
+}
+
+impl Validate for Play {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "url", &self.url);
+    }
 }
@@ -3,6 +3,7 @@ use crate::verbs::play::Play;
 use crate::verbs::say::Say;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "verb")]
 #[serde(rename_all = "camelCase")]
 pub enum PlaySay {
@@ -1,11 +1,16 @@
 use crate::verbs::recognizer::Recognizer;
 use crate::verbs::synthesizer::Synthesizer;
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Rasa {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub url: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -27,6 +32,7 @@ pub struct Rasa {
 impl Rasa {
     pub fn new(url: String) -> Rasa {
         Rasa {
+            id: None,
             url,
             prompt: None,
             event_hook: None,
@@ -60,7 +66,11 @@ impl Rasa {
         self.recognizer = recognizer;
         self
     }
-    
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Rasa {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for Rasa {
@@ -74,3 +84,5 @@ impl Into<Vec<Verb>> for Rasa {
         vec![self.into()]
     }
 }
+
+impl Validate for Rasa {}
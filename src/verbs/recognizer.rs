@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use crate::vendors::openai::OpenaiRecognizer;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "vendor")]
 pub enum Recognizer {
@@ -1,15 +1,25 @@
 use serde::{Deserialize, Serialize};
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Redirect {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub action_hook: String,
 }
 
 impl Redirect {
     pub fn new(action_hook: String) -> Redirect {
-        Redirect { action_hook }
+        Redirect { id: None, action_hook }
+    }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Redirect {
+        self.id = id;
+        self
     }
 }
 
@@ -24,3 +34,9 @@ impl Into<Vec<Verb>> for Redirect {
         vec![self.into()]
     }
 }
+
+impl Validate for Redirect {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "actionHook", &self.action_hook);
+    }
+}
@@ -1,9 +1,14 @@
 use crate::verbs::synthesizer::Synthesizer;
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Say {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub text: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -21,6 +26,7 @@ pub struct Say {
 impl Say {
     pub fn new(text: String) -> Say {
         Say {
+            id: None,
             text,
             say_loop: Some(1),
             synthesizer: None,
@@ -42,6 +48,11 @@ impl Say {
         self.say_loop = say_loop;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Say {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for Say {
@@ -56,4 +67,8 @@ impl Into<Vec<Verb>> for Say {
     }
 }
 
-
+impl Validate for Say {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "text", &self.text);
+    }
+}
@@ -0,0 +1,399 @@
+use crate::verbs::conference::Conference;
+use crate::verbs::config::Config;
+use crate::verbs::dequeue::Dequeue;
+use crate::verbs::dial::Dial;
+use crate::verbs::dialogflow::DialogFlow;
+use crate::verbs::dtmf::Dtmf;
+use crate::verbs::dub::DubData;
+use crate::verbs::enqueue::Enqueue;
+use crate::verbs::gather::Gather;
+use crate::verbs::hangup::Hangup;
+use crate::verbs::leave::Leave;
+use crate::verbs::lex::Lex;
+use crate::verbs::listen::Listen;
+use crate::verbs::llm::Llm;
+use crate::verbs::message::Message;
+use crate::verbs::pause::Pause;
+use crate::verbs::play::Play;
+use crate::verbs::rasa::Rasa;
+use crate::verbs::redirect::Redirect;
+use crate::verbs::say::Say;
+use crate::verbs::sipdecline::SipDecline;
+use crate::verbs::siprec::SipRec;
+use crate::verbs::siprefer::SipRefer;
+use crate::verbs::tag::Tag;
+use crate::verbs::transcribe::Transcribe;
+use crate::verbs::verb::Verb;
+use schemars::{Schema, schema_for};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// JSON Schema for a single verb of any kind, tagged by `verb`.
+pub fn verb_schema() -> Schema {
+    schema_for!(Verb)
+}
+
+/// JSON Schema for each verb, keyed by the `verb` name sent to jambonz.
+/// `Verb::SipRec` is left out: jambonz has no `sipRec` verb, SIPREC is
+/// started with the `record` option of `config`.
+pub fn verb_schemas() -> BTreeMap<&'static str, Schema> {
+    BTreeMap::from([
+        ("conference", schema_for!(Conference)),
+        ("config", schema_for!(Config)),
+        ("dequeue", schema_for!(Dequeue)),
+        ("dial", schema_for!(Dial)),
+        ("dialogflow", schema_for!(DialogFlow)),
+        ("dub", schema_for!(DubData)),
+        ("dtmf", schema_for!(Dtmf)),
+        ("enqueue", schema_for!(Enqueue)),
+        ("gather", schema_for!(Gather)),
+        ("hangup", schema_for!(Hangup)),
+        ("leave", schema_for!(Leave)),
+        ("lex", schema_for!(Lex)),
+        ("listen", schema_for!(Listen)),
+        ("llm", schema_for!(Llm)),
+        ("message", schema_for!(Message)),
+        ("sip:decline", schema_for!(SipDecline)),
+        ("pause", schema_for!(Pause)),
+        ("play", schema_for!(Play)),
+        ("redirect", schema_for!(Redirect)),
+        ("rasa", schema_for!(Rasa)),
+        ("say", schema_for!(Say)),
+        ("sip:refer", schema_for!(SipRefer)),
+        ("tag", schema_for!(Tag)),
+        ("transcribe", schema_for!(Transcribe)),
+    ])
+}
+
+/// `specs.json` from `@jambonz/verb-specifications`, transcribed by hand.
+/// Properties are typed as in the original: `"string|array"`, `"#target"`
+/// for another entry, `["#target"]` for an array of them.
+///
+/// To check against the unmodified upstream file as well, set
+/// `JAMBONZ_VERB_SPECIFICATIONS` to its path when running the tests.
+const SPECIFICATIONS: &str = include_str!("../assets/verb-specifications.json");
+
+/// Upstream entries missing from `SPECIFICATIONS`, so nothing under them is
+/// checked. Both hold an options object per vendor.
+const UNTRANSCRIBED: [&str; 2] = ["recognizer", "synthesizer"];
+
+/// Compares each verb schema against the spec. Returns one line per property
+/// jambonz does not know, whose type differs, or that jambonz requires.
+/// Verbs and nested objects missing from the spec are not checked.
+fn schema_drift(specs: &Value) -> Vec<String> {
+    let mut drift = vec![];
+    for (name, schema) in verb_schemas() {
+        let root = schema.as_value();
+        compare_schema(specs, name, root, root, name, &mut drift);
+    }
+    drift
+}
+
+fn compare_schema(
+    specs: &Value,
+    spec: &str,
+    root: &Value,
+    schema: &Value,
+    path: &str,
+    drift: &mut Vec<String>,
+) {
+    let Some(spec_properties) = specs[spec]["properties"].as_object() else {
+        return;
+    };
+    let properties = properties(root, schema);
+    for (name, property) in &properties {
+        let path = format!("{}.{}", path, name);
+        let Some(spec_property) = spec_properties.get(name) else {
+            drift.push(format!("{}: not in the jambonz spec", path));
+            continue;
+        };
+        let types = types(root, property);
+        let spec_types = spec_types(spec_property);
+        if !types.is_empty() && types.is_disjoint(&spec_types) {
+            drift.push(format!(
+                "{}: {:?} but the jambonz spec has {:?}",
+                path, types, spec_types
+            ));
+            continue;
+        }
+        match nested(spec_property) {
+            Some((nested, false)) => compare_schema(specs, nested, root, property, &path, drift),
+            Some((nested, true)) => {
+                if let Some(items) = items(root, property) {
+                    compare_schema(specs, nested, root, items, &path, drift);
+                }
+            }
+            None => {}
+        }
+    }
+    for required in specs[spec]["required"].as_array().into_iter().flatten() {
+        if let Some(required) = required.as_str()
+            && !properties.contains_key(required)
+        {
+            drift.push(format!(
+                "{}.{}: required by the jambonz spec",
+                path, required
+            ));
+        }
+    }
+}
+
+/// Checks serialized verbs against the spec, as `schema_drift` does for schemas.
+fn value_drift(specs: &Value, spec: &str, value: &Value, path: &str, drift: &mut Vec<String>) {
+    let (Some(spec_properties), Some(object)) =
+        (specs[spec]["properties"].as_object(), value.as_object())
+    else {
+        return;
+    };
+    for (name, value) in object {
+        if name == "verb" {
+            continue;
+        }
+        let path = format!("{}.{}", path, name);
+        let Some(spec_property) = spec_properties.get(name) else {
+            drift.push(format!("{}: not in the jambonz spec", path));
+            continue;
+        };
+        let spec_types = spec_types(spec_property);
+        if !value.is_null() && !spec_types.contains(value_type(value)) {
+            drift.push(format!(
+                "{}: {} but the jambonz spec has {:?}",
+                path,
+                value_type(value),
+                spec_types
+            ));
+            continue;
+        }
+        match (nested(spec_property), value) {
+            (Some((nested, false)), _) => value_drift(specs, nested, value, &path, drift),
+            (Some((nested, true)), Value::Array(items)) => {
+                for (index, item) in items.iter().enumerate() {
+                    value_drift(specs, nested, item, &format!("{}[{}]", path, index), drift);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The definition a `$ref` points at, if `schema` is one.
+fn reference<'a>(root: &'a Value, schema: &Value) -> Option<&'a Value> {
+    let name = schema["$ref"].as_str()?.strip_prefix("#/$defs/")?;
+    root["$defs"].get(name)
+}
+
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    reference(root, schema)
+        .map(|schema| resolve(root, schema))
+        .unwrap_or(schema)
+}
+
+/// Properties of an object schema, merged across `$ref`, `anyOf` and `oneOf`
+/// so that enums of structs (e.g. `Target`) cover every variant and its tag.
+fn properties(root: &Value, schema: &Value) -> Map<String, Value> {
+    let mut properties = schema["properties"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+    if let Some(reference) = reference(root, schema) {
+        properties.extend(self::properties(root, reference));
+    }
+    for key in ["anyOf", "oneOf", "allOf"] {
+        for variant in schema[key].as_array().into_iter().flatten() {
+            properties.extend(self::properties(root, variant));
+        }
+    }
+    properties
+}
+
+fn items<'a>(root: &'a Value, schema: &'a Value) -> Option<&'a Value> {
+    let schema = resolve(root, schema);
+    if schema.get("items").is_some() {
+        return Some(&schema["items"]);
+    }
+    schema["anyOf"]
+        .as_array()?
+        .iter()
+        .find_map(|variant| items(root, variant))
+}
+
+/// The JSON types a schema allows, with `integer` as `number` and without `null`.
+/// Empty if the schema allows any value.
+fn types(root: &Value, schema: &Value) -> BTreeSet<&'static str> {
+    if let Some(reference) = reference(root, schema) {
+        return types(root, reference);
+    }
+    let mut types: BTreeSet<&'static str> = match &schema["type"] {
+        Value::String(name) => [json_type(name)].into_iter().flatten().collect(),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(|name| json_type(name.as_str()?))
+            .collect(),
+        _ if schema.get("properties").is_some() => BTreeSet::from(["object"]),
+        _ => BTreeSet::new(),
+    };
+    for key in ["anyOf", "oneOf"] {
+        for variant in schema[key].as_array().into_iter().flatten() {
+            types.extend(self::types(root, variant));
+        }
+    }
+    types
+}
+
+fn json_type(name: &str) -> Option<&'static str> {
+    match name {
+        "string" => Some("string"),
+        "integer" | "number" => Some("number"),
+        "boolean" => Some("boolean"),
+        "object" => Some("object"),
+        "array" => Some("array"),
+        _ => None,
+    }
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn spec_types(spec: &Value) -> BTreeSet<&'static str> {
+    match spec {
+        Value::String(name) if name.starts_with('#') => BTreeSet::from(["object"]),
+        Value::String(names) => names.split('|').filter_map(json_type).collect(),
+        Value::Array(_) => BTreeSet::from(["array"]),
+        _ => spec["type"]
+            .as_str()
+            .and_then(json_type)
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// The spec entry a property refers to, and whether it is an array of them.
+fn nested(spec: &Value) -> Option<(&str, bool)> {
+    match spec {
+        Value::String(name) => Some((name.strip_prefix('#')?, false)),
+        Value::Array(items) => Some((items.first()?.as_str()?.strip_prefix('#')?, true)),
+        _ => None,
+    }
+}
+
+#[test]
+fn verb_specifications() {
+    use crate::payload::builder::VerbTrait;
+    use crate::payload::ws::Verbs;
+    use crate::verbs::amd::Amd;
+    use crate::verbs::dub::DubTrack;
+    use crate::verbs::siprec::SipRecAction;
+    use crate::verbs::target::Phone;
+
+    let specs: Value = serde_json::from_str(SPECIFICATIONS).unwrap();
+    assert_eq!(schema_drift(&specs), Vec::<String>::new());
+
+    // Everything the transcription leaves out is listed, and nothing more.
+    let mut names: BTreeSet<&str> = verb_schemas().into_keys().collect();
+    for spec in specs.as_object().unwrap().values() {
+        for property in spec["properties"].as_object().unwrap().values() {
+            names.extend(nested(property).map(|(name, _)| name));
+        }
+    }
+    let untranscribed: Vec<&str> = names
+        .into_iter()
+        .filter(|name| specs.get(*name).is_none())
+        .collect();
+    assert_eq!(untranscribed, UNTRANSCRIBED);
+
+    if let Ok(path) = std::env::var("JAMBONZ_VERB_SPECIFICATIONS") {
+        let upstream: Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(schema_drift(&upstream), Vec::<String>::new());
+    }
+
+    let mut gather = Gather::new("/menu");
+    gather.digits().num_digits(Some(4));
+    gather.say(Some(Say::new("Enter your pin".to_string())));
+    let mut dialogflow = DialogFlow::new(
+        "my-project".to_string(),
+        "en-GB".to_string(),
+        "{}".to_string(),
+    );
+    dialogflow.bargein(Some(true)).pass_dtmf_as_text_input(Some(true));
+    let mut record = SipRec::new(
+        SipRecAction::StartCallRecording,
+        "sip:recorder@example.com".to_string(),
+    );
+    record.set_recording_id(Some("rec-1".to_string()));
+    let mut config = Config::new();
+    config.record(Some(record)).amd(Some(Amd::new("/amd".to_string())));
+    let mut dial = Dial::new(
+        "+441234567890",
+        vec![Phone::new("+447777777777".to_string()).into()],
+    );
+    let mut music = DubData::new(DubTrack::PlayOnTrack, "music".to_string());
+    music
+        .play(Some("https://example.com/hold.mp3".to_string()))
+        .dub_loop(Some(true));
+    dial.add_dub(DubData::new(DubTrack::AddTrack, "music".to_string()))
+        .add_dub(music);
+    let mut lex = Lex::new(
+        "bot".to_string(),
+        "alias".to_string(),
+        "key".to_string(),
+        "secret".to_string(),
+        "eu-west-2".to_string(),
+    );
+    lex.intent(Some("BookFlight".to_string()));
+
+    let mut verbs = Verbs::new("1234");
+    verbs
+        .gather(gather)
+        .dialog_flow(dialogflow)
+        .config(config)
+        .dial(dial)
+        .sip_refer(SipRefer::new("sip:+441234567890@example.com".to_string()))
+        .lex(lex)
+        .llm(Llm::openai("gpt-4o-realtime-preview".to_string(), "sk-test".to_string()));
+    let data = serde_json::to_value(&verbs.data).unwrap();
+    let mut drift = vec![];
+    for (index, verb) in data.as_array().unwrap().iter().enumerate() {
+        let name = verb["verb"].as_str().unwrap();
+        value_drift(&specs, name, verb, &format!("$[{}]", index), &mut drift);
+    }
+    assert_eq!(drift, Vec::<String>::new());
+
+    assert_eq!(data[0]["numDigits"], 4);
+    assert!(data[0].get("maxDigits").is_none());
+    assert_eq!(data[1]["verb"], "dialogflow");
+    assert_eq!(data[1]["bargein"], true);
+    assert_eq!(
+        data[2]["record"]["siprecServerURL"],
+        "sip:recorder@example.com"
+    );
+    assert_eq!(data[2]["record"]["recordingID"], "rec-1");
+    assert_eq!(data[2]["amd"]["actionHook"], "/amd");
+    assert_eq!(data[3]["dub"][1]["loop"], true);
+    assert!(data[3]["dub"][1].get("verb").is_none());
+    assert_eq!(data[5]["intent"]["name"], "BookFlight");
+
+    let mut drifted = specs.clone();
+    drifted["gather"]["properties"]["numDigits"] = Value::from("string");
+    drifted["gather"]["required"] = serde_json::json!(["speechTimeout"]);
+    drifted["dialogflow"]["properties"]
+        .as_object_mut()
+        .unwrap()
+        .remove("bargein");
+    let drift = schema_drift(&drifted);
+    assert!(drift.contains(&"dialogflow.bargein: not in the jambonz spec".to_string()));
+    assert!(drift.contains(&"gather.speechTimeout: required by the jambonz spec".to_string()));
+    assert!(
+        drift
+            .iter()
+            .any(|line| line.starts_with("gather.numDigits: {\"number\"}"))
+    );
+}
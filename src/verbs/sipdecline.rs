@@ -1,18 +1,23 @@
 use crate::shared::shared::SIPStatus;
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SipDecline {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<SIPStatus>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
 
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
 }
 
@@ -31,6 +36,7 @@ impl Into<Vec<Verb>> for SipDecline {
 impl SipDecline {
     pub fn new(status: SIPStatus) -> SipDecline {
         SipDecline {
+            id: None,
             status: Some(status),
             reason: None,
             headers: HashMap::new(),
@@ -41,6 +47,7 @@ impl SipDecline {
         let mut map = HashMap::new();
         map.insert("X-Reason".to_string(), x_reason);
         SipDecline {
+            id: None,
             status: Some(SIPStatus::InternalServerError),
             reason: Some(reason),
             headers: map,
@@ -51,6 +58,7 @@ impl SipDecline {
         let mut map = HashMap::new();
         map.insert("X-Reason".to_string(), x_reason);
         SipDecline {
+            id: None,
             status: Some(SIPStatus::Unauthorized),
             reason: Some(reason),
             headers: map,
@@ -61,6 +69,7 @@ impl SipDecline {
         let mut map = HashMap::new();
         map.insert("X-Reason".to_string(), x_reason);
         SipDecline {
+            id: None,
             status: Some(SIPStatus::Decline),
             reason: Some(reason),
             headers: map,
@@ -71,6 +80,7 @@ impl SipDecline {
         let mut map = HashMap::new();
         map.insert("X-Reason".to_string(), "Not Implemented".to_string());
         SipDecline {
+            id: None,
             status: Some(SIPStatus::NotImplemented),
             reason: Some(reason),
             headers: map,
@@ -97,4 +107,11 @@ impl SipDecline {
         self.headers = headers;
         self
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut SipDecline {
+        self.id = id;
+        self
+    }
 }
+
+impl Validate for SipDecline {}
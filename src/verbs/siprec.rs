@@ -1,13 +1,17 @@
+use crate::verbs::validate::Validate;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SipRec {
     pub action: SipRecAction,
 
+    #[serde(rename = "siprecServerURL", alias = "siprecServerUrl")]
     pub siprec_server_url: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "recordingID", alias = "recordingId")]
     pub recording_id: Option<String>,
 }
 
@@ -19,8 +23,17 @@ impl SipRec {
             recording_id: None,
         }
     }
+    pub fn set_siprec_server_url(&mut self, siprec_server_url: String) -> &mut SipRec {
+        self.siprec_server_url = siprec_server_url;
+        self
+    }
+
+    /// Leaves the server url unchanged for `None`.
+    #[deprecated(note = "use set_siprec_server_url")]
     pub fn siprec_server_url(&mut self, siprec_server_url: Option<String>) -> &mut SipRec {
-        self.recording_id = siprec_server_url;
+        if let Some(siprec_server_url) = siprec_server_url {
+            self.siprec_server_url = siprec_server_url;
+        }
         self
     }
     pub fn set_recording_id(&mut self, recording_id: Option<String>) -> &mut SipRec {
@@ -30,6 +43,7 @@ impl SipRec {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum SipRecAction {
     StartCallRecording,
@@ -37,3 +51,5 @@ pub enum SipRecAction {
     PauseCallRecording,
     ResumeCallRecording,
 }
+
+impl Validate for SipRec {}
@@ -1,10 +1,15 @@
+use crate::verbs::validate::{require, Validate, ValidationError};
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct SipRefer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     pub refer_to: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -16,13 +21,14 @@ pub struct SipRefer {
     #[serde(skip_serializing_if = "Option::is_none")]
     referred_by: Option<String>,
 
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
 }
 
 impl SipRefer {
     pub fn new(refer_to: String) -> SipRefer {
         SipRefer {
+            id: None,
             refer_to,
             action_hook: None,
             event_hook: None,
@@ -60,8 +66,11 @@ impl SipRefer {
         self.headers.insert(key.to_string(), value.to_string());
         self
     }
-    
-    
+
+    pub fn id(&mut self, id: Option<String>) -> &mut SipRefer {
+        self.id = id;
+        self
+    }
 }
 impl Into<Verb> for SipRefer {
     fn into(self) -> Verb {
@@ -74,3 +83,9 @@ impl Into<Vec<Verb>> for SipRefer {
         vec![self.into()]
     }
 }
+
+impl Validate for SipRefer {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        require(errors, path, "referTo", &self.refer_to);
+    }
+}
@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 // In-progress
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Synthesizer {
     pub vendor: SynthesizerVendor,
@@ -25,6 +26,7 @@ pub struct Synthesizer {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum SynthesizerOptions {
     Google(GoogleSynthesizer),
@@ -137,6 +139,7 @@ impl Default for Synthesizer {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum SynthesizerVendor {
     Default,
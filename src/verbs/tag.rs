@@ -1,32 +1,45 @@
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Tag {
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
-    pub data: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub data: HashMap<String, Value>,
 }
 
 impl Tag {
     pub fn new() -> Tag {
         Tag {
+            id: None,
             data: HashMap::new(),
         }
     }
-    pub fn insert(&mut self, key: String, val: String) -> &mut Tag {
-        self.data.insert(key, val);
+    pub fn insert<V: Into<Value>>(&mut self, key: String, val: V) -> &mut Tag {
+        self.data.insert(key, val.into());
         self
     }
 
     pub fn extend(&mut self, data: HashMap<String, String>) -> &mut Tag {
-        self.data.extend(data);
+        self.data
+            .extend(data.into_iter().map(|(key, val)| (key, Value::String(val))));
         self
     }
 
     pub fn replace(&mut self, data: HashMap<String, String>) -> &mut Tag {
-        self.data = data;
+        self.data.clear();
+        self.extend(data)
+    }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Tag {
+        self.id = id;
         self
     }
 }
@@ -41,3 +54,5 @@ impl Into<Vec<Verb>> for Tag {
         vec![self.into()]
     }
 }
+
+impl Validate for Tag {}
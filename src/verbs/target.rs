@@ -1,8 +1,10 @@
 use crate::verbs::auth::WSAuth;
+use crate::verbs::validate::{require, Validate, ValidationError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "type")]
 pub enum Target {
@@ -13,6 +15,7 @@ pub enum Target {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Phone {
     /// A telephone number in E.164 format.
     pub number: String,
@@ -35,7 +38,7 @@ pub struct Phone {
 
     /// An object containing arbitrary SIP headers
     /// to apply to the outbound call attempt(s).
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
 }
 
@@ -87,6 +90,7 @@ impl Phone {
     }
 }
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Sip {
     /// Sip uri to send call to
     /// e.g. (sip:+441234567890:5060)
@@ -107,7 +111,7 @@ pub struct Sip {
 
     /// An object containing arbitrary SIP headers
     /// to apply to the outbound call attempt(s).
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
 }
 
@@ -169,6 +173,7 @@ impl Sip {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct User {
     /// Registered sip user, including domain
     /// (e.g. "joeb@sip.jambonz.org")
@@ -184,7 +189,7 @@ pub struct User {
 
     /// An object containing arbitrary SIP headers
     /// to apply to the outbound call attempt(s).
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
 
     /// Adds X-Override-To: +441234567890 INVITE from FS
@@ -252,6 +257,7 @@ impl User {
     }
 }
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Teams {
     /// The phone number that has been mapped to the
     /// teams user by the Microsoft Teams administrator
@@ -273,12 +279,13 @@ pub struct Teams {
 
     /// if true, dial directly into user’s
     /// voicemail to leave a message
+    #[serde(rename = "vmail", alias = "voicemail")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub voicemail: Option<bool>,
 
     /// An object containing arbitrary SIP headers
     /// to apply to the outbound call attempt(s).
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub headers: HashMap<String, String>,
 }
 
@@ -345,3 +352,21 @@ impl Teams {
         self
     }
 }
+
+impl Validate for Target {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        match self {
+            Target::Phone(phone) => require(errors, path, "number", &phone.number),
+            Target::Sip(sip) => {
+                if !sip.sip_uri.starts_with("sip:") && !sip.sip_uri.starts_with("sips:") {
+                    errors.push(ValidationError::new(
+                        format!("{}.sipUri", path),
+                        "must start with sip: or sips:",
+                    ));
+                }
+            }
+            Target::User(user) => require(errors, path, "name", &user.name),
+            Target::Teams(teams) => require(errors, path, "number", &teams.number),
+        }
+    }
+}
@@ -1,9 +1,14 @@
 use crate::verbs::recognizer::Recognizer;
+use crate::verbs::validate::Validate;
 use crate::verbs::verb::Verb;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Transcribe {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
     #[serde(rename = "transcriptionHook")]
     pub transcription_hook: String,
     pub recognizer: Recognizer,
@@ -12,10 +17,16 @@ pub struct Transcribe {
 impl Transcribe {
     pub fn new(transcription_hook: String, recognizer: Recognizer) -> Transcribe {
         Transcribe {
+            id: None,
             transcription_hook,
             recognizer,
         }
     }
+
+    pub fn id(&mut self, id: Option<String>) -> &mut Transcribe {
+        self.id = id;
+        self
+    }
 }
 
 impl Into<Verb> for Transcribe {
@@ -29,3 +40,5 @@ impl Into<Vec<Verb>> for Transcribe {
         vec![self.into()]
     }
 }
+
+impl Validate for Transcribe {}
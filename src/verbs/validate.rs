@@ -0,0 +1,145 @@
+use crate::payload::ws::Verbs;
+use crate::verbs::verb::Verb;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Checks constraints that jambonz would otherwise reject at runtime.
+pub trait Validate {
+    /// Pushes an error for each problem found. `path` is the JSON path
+    /// of `self`, e.g. "$[2]" for the third verb in a list.
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {}
+
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        self.validate_at("$", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ValidationError {
+    /// JSON path of the offending value, using the names sent to jambonz.
+    pub path: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(path: String, message: &str) -> ValidationError {
+        ValidationError {
+            path,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Pushes an error if a required string is empty.
+pub(crate) fn require(errors: &mut Vec<ValidationError>, path: &str, field: &str, value: &str) {
+    if value.trim().is_empty() {
+        errors.push(ValidationError::new(
+            format!("{}.{}", path, field),
+            "must not be empty",
+        ));
+    }
+}
+
+impl Validate for Verb {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        match self {
+            Verb::Conference(verb) => verb.validate_at(path, errors),
+            Verb::Config(verb) => verb.validate_at(path, errors),
+            Verb::Dequeue(verb) => verb.validate_at(path, errors),
+            Verb::Dial(verb) => verb.validate_at(path, errors),
+            Verb::DialogFlow(verb) => verb.validate_at(path, errors),
+            Verb::Dub(verb) => verb.validate_at(path, errors),
+            Verb::Dtmf(verb) => verb.validate_at(path, errors),
+            Verb::Enqueue(verb) => verb.validate_at(path, errors),
+            Verb::Gather(verb) => verb.validate_at(path, errors),
+            Verb::Hangup(verb) => verb.validate_at(path, errors),
+            Verb::Leave(verb) => verb.validate_at(path, errors),
+            Verb::Lex(verb) => verb.validate_at(path, errors),
+            Verb::Listen(verb) => verb.validate_at(path, errors),
+            Verb::Llm(verb) => verb.validate_at(path, errors),
+            Verb::Message(verb) => verb.validate_at(path, errors),
+            Verb::SipDecline(verb) => verb.validate_at(path, errors),
+            Verb::SipRec(verb) => verb.validate_at(path, errors),
+            Verb::Pause(verb) => verb.validate_at(path, errors),
+            Verb::Play(verb) => verb.validate_at(path, errors),
+            Verb::Redirect(verb) => verb.validate_at(path, errors),
+            Verb::Rasa(verb) => verb.validate_at(path, errors),
+            Verb::Say(verb) => verb.validate_at(path, errors),
+            Verb::SipRefer(verb) => verb.validate_at(path, errors),
+            Verb::Tag(verb) => verb.validate_at(path, errors),
+            Verb::Transcribe(verb) => verb.validate_at(path, errors),
+            Verb::Unknown(verb) => {}
+        }
+    }
+}
+
+impl Validate for Vec<Verb> {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        for (index, verb) in self.iter().enumerate() {
+            verb.validate_at(&format!("{}[{}]", path, index), errors);
+        }
+    }
+}
+
+impl Validate for Verbs {
+    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        self.data.validate_at(&format!("{}.data", path), errors);
+    }
+}
+
+#[test]
+fn validate_verbs() {
+    use crate::payload::builder::VerbTrait;
+    use crate::verbs::config::Config;
+    use crate::verbs::dial::Dial;
+    use crate::verbs::gather::Gather;
+    use crate::verbs::say::Say;
+    use crate::verbs::target::{Phone, Target};
+
+    let mut gather = Gather::new("/menu");
+    gather.min_digits(Some(4)).max_digits(Some(2));
+    gather.input = Some(vec![]);
+    gather.say(Some(Say::new(" ".to_string())));
+
+    let targets: Vec<Target> = (0..11)
+        .map(|index| Phone::new(format!("+4477009001{:02}", index)).into())
+        .collect();
+    let mut config = Config::new();
+    config.reset = Some(vec!["recognizer".to_string(), "vad".to_string()]);
+
+    let errors = Verbs::new("1234")
+        .say_text("Welcome")
+        .gather(gather)
+        .dial(Dial::new("+441234567890", targets))
+        .config(config)
+        .validate()
+        .unwrap_err();
+    let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "$.data[1].input: must include \"digits\" or \"speech\"",
+            "$.data[1].minDigits: must not be greater than maxDigits",
+            "$.data[1].say.text: must not be empty",
+            "$.data[2].target: must not have more than 10 targets",
+            "$.data[3].reset[1]: must be \"recognizer\" or \"synthesizer\"",
+        ]
+    );
+    // No input means digits, as in jambonz.
+    assert_eq!(Gather::new("/menu").validate(), Ok(()));
+
+    let verbs: Vec<Verb> = Say::new("Goodbye".to_string()).into();
+    assert!(verbs.validate().is_ok());
+}
@@ -2,6 +2,7 @@ use crate::verbs::vendors::vad::Vad;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct AWSRecognizer {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -39,6 +40,7 @@ pub struct AWSRecognizer {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum AWSFilterMethod {
     Remove,
@@ -48,11 +50,13 @@ pub enum AWSFilterMethod {
 
 //todo
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AwsSynthesizer {
     
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AwsTTSLanguage {
     #[serde(rename = "arb")]
     Arabic,
@@ -139,6 +143,7 @@ pub enum AwsTTSLanguage {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AwsAsrLanguage {
     #[serde(rename = "ab-GE")]
     Abkhaz,
@@ -1,7 +1,11 @@
+use crate::verbs::llm::LlmAuth;
 use serde::{Deserialize, Serialize};
 use crate::verbs::vendors::vad::Vad;
+use serde_json::Value;
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DeepgramRecognizer {
 
@@ -35,5 +39,182 @@ pub struct DeepgramRecognizer {
 
 //todo
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct DeepgramOptions {}
+
+/// Deepgram Voice Agent speech-to-speech options for the llm verb.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct DeepgramLlm {
+    pub model: String,
+
+    pub auth: LlmAuth,
+
+    pub llm_options: DeepgramLlmOptions,
+}
+
+impl DeepgramLlm {
+    pub fn new(model: String, api_key: String) -> DeepgramLlm {
+        DeepgramLlm {
+            model,
+            auth: LlmAuth::new(api_key),
+            llm_options: DeepgramLlmOptions {
+                settings_configuration: DeepgramSettingsConfiguration::new(),
+            },
+        }
+    }
+
+    pub fn agent(&mut self, agent: DeepgramAgent) -> &mut DeepgramLlm {
+        self.llm_options.settings_configuration.agent = agent;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct DeepgramLlmOptions {
+    pub settings_configuration: DeepgramSettingsConfiguration,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeepgramSettingsConfiguration {
+    #[serde(rename = "type")]
+    pub settings_type: String,
+
+    pub agent: DeepgramAgent,
+}
+
+impl Default for DeepgramSettingsConfiguration {
+    fn default() -> DeepgramSettingsConfiguration {
+        DeepgramSettingsConfiguration::new()
+    }
+}
+
+impl DeepgramSettingsConfiguration {
+    pub fn new() -> DeepgramSettingsConfiguration {
+        DeepgramSettingsConfiguration {
+            settings_type: "SettingsConfiguration".to_string(),
+            agent: DeepgramAgent::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeepgramAgent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen: Option<DeepgramAgentModel>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub think: Option<DeepgramThink>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speak: Option<DeepgramAgentModel>,
+}
+
+impl Default for DeepgramAgent {
+    fn default() -> DeepgramAgent {
+        DeepgramAgent::new()
+    }
+}
+
+impl DeepgramAgent {
+    pub fn new() -> DeepgramAgent {
+        DeepgramAgent {
+            listen: None,
+            think: None,
+            speak: None,
+        }
+    }
+
+    pub fn listen(&mut self, model: Option<String>) -> &mut DeepgramAgent {
+        self.listen = model.map(|model| DeepgramAgentModel { model });
+        self
+    }
+
+    pub fn think(&mut self, think: Option<DeepgramThink>) -> &mut DeepgramAgent {
+        self.think = think;
+        self
+    }
+
+    pub fn speak(&mut self, model: Option<String>) -> &mut DeepgramAgent {
+        self.speak = model.map(|model| DeepgramAgentModel { model });
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeepgramAgentModel {
+    pub model: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeepgramThink {
+    pub provider: DeepgramThinkProvider,
+
+    pub model: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub functions: Option<Vec<DeepgramFunction>>,
+}
+
+impl DeepgramThink {
+    pub fn new(provider: String, model: String) -> DeepgramThink {
+        DeepgramThink {
+            provider: DeepgramThinkProvider {
+                provider_type: provider,
+            },
+            model,
+            instructions: None,
+            functions: None,
+        }
+    }
+
+    pub fn instructions(&mut self, instructions: Option<String>) -> &mut DeepgramThink {
+        self.instructions = instructions;
+        self
+    }
+
+    pub fn functions(&mut self, functions: Option<Vec<DeepgramFunction>>) -> &mut DeepgramThink {
+        self.functions = functions;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeepgramThinkProvider {
+    #[serde(rename = "type")]
+    pub provider_type: String,
+}
+
+/// A function the Deepgram agent may call. Functions without a `url`
+/// are client-side and are delivered to the llm verb's `toolHook`.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DeepgramFunction {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    pub parameters: Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<HashMap<String, String>>>,
+}
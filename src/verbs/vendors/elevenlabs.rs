@@ -1,7 +1,134 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 //todo https://docs.jambonz.org/verbs/verbs/synthesizer#elevenlabs
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ElevenlabsSynthesizer {
 
-}
\ No newline at end of file
+}
+
+/// ElevenLabs Conversational AI agent options for the llm verb.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ElevenlabsLlm {
+    pub auth: ElevenlabsAuth,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm_options: Option<ElevenlabsLlmOptions>,
+}
+
+impl ElevenlabsLlm {
+    pub fn new(agent_id: String, api_key: Option<String>) -> ElevenlabsLlm {
+        ElevenlabsLlm {
+            auth: ElevenlabsAuth { agent_id, api_key },
+            llm_options: None,
+        }
+    }
+
+    pub fn llm_options(&mut self, llm_options: Option<ElevenlabsLlmOptions>) -> &mut ElevenlabsLlm {
+        self.llm_options = llm_options;
+        self
+    }
+}
+
+/// The api key is only required for private agents.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ElevenlabsAuth {
+    pub agent_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ElevenlabsLlmOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_sample_rate: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_sample_rate: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation_initiation_client_data: Option<ElevenlabsClientData>,
+}
+
+impl Default for ElevenlabsLlmOptions {
+    fn default() -> ElevenlabsLlmOptions {
+        ElevenlabsLlmOptions::new()
+    }
+}
+
+impl ElevenlabsLlmOptions {
+    pub fn new() -> ElevenlabsLlmOptions {
+        ElevenlabsLlmOptions {
+            input_sample_rate: None,
+            output_sample_rate: None,
+            conversation_initiation_client_data: None,
+        }
+    }
+
+    pub fn input_sample_rate(&mut self, sample_rate: Option<u32>) -> &mut ElevenlabsLlmOptions {
+        self.input_sample_rate = sample_rate;
+        self
+    }
+
+    pub fn output_sample_rate(&mut self, sample_rate: Option<u32>) -> &mut ElevenlabsLlmOptions {
+        self.output_sample_rate = sample_rate;
+        self
+    }
+
+    pub fn client_data(&mut self, client_data: Option<ElevenlabsClientData>) -> &mut ElevenlabsLlmOptions {
+        self.conversation_initiation_client_data = client_data;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ElevenlabsClientData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation_config_override: Option<ElevenlabsConfigOverride>,
+
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default)]
+    pub dynamic_variables: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ElevenlabsConfigOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<ElevenlabsAgentOverride>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts: Option<ElevenlabsTtsOverride>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ElevenlabsAgentOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<ElevenlabsPrompt>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_message: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ElevenlabsPrompt {
+    pub prompt: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ElevenlabsTtsOverride {
+    pub voice_id: String,
+}
@@ -2,6 +2,7 @@ use crate::verbs::vendors::vad::Vad;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct GoogleRecognizer {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -60,6 +61,7 @@ pub struct GoogleRecognizer {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct GoogleSynthesizer {
 
@@ -87,6 +89,7 @@ impl GoogleSynthesizer {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum GoogleSpeechModel {
     PhoneCall,
@@ -102,6 +105,7 @@ pub enum GoogleSpeechModel {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum GoogleGender {
     Male,
@@ -109,6 +113,7 @@ pub enum GoogleGender {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum GoogleInteractionType {
     Discussion,
@@ -122,6 +127,7 @@ pub enum GoogleInteractionType {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum GoogleVoice {
     #[serde(rename = "ar-XA-Standard-A")]
     ArabicStandardAFemale,
@@ -585,6 +591,7 @@ pub enum GoogleVoice {
 //  Currently copied from ASR Languages
 //  Reference assets/google_tts_languages to build enum.
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum GoogleTTSLanguage {
     #[serde(rename = "af-ZA")]
     AfrikaansSouthAfrica,
@@ -845,6 +852,7 @@ pub enum GoogleTTSLanguage {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum GoogleRecognizerLanguage {
     #[serde(rename = "af-ZA")]
     AfrikaansSouthAfrica,
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use crate::verbs::vendors::vad::Vad;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct IbmRecognizer {
 
@@ -33,6 +34,7 @@ pub struct IbmRecognizer {
 
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct IBMOptions {
     pub stt_api_key: String,
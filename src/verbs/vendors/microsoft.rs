@@ -2,6 +2,7 @@ use crate::verbs::vendors::vad::Vad;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct MSRecognizer {
 
@@ -53,6 +54,7 @@ pub struct MSRecognizer {
 
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum MSOutputFormat {
     Simple,
@@ -60,6 +62,7 @@ pub enum MSOutputFormat {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum MSProfanityOption {
     Masked,
@@ -68,6 +71,7 @@ pub enum MSProfanityOption {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct AzureOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -12,5 +12,6 @@ pub mod cartesia;
 pub mod elevenlabs;
 pub mod playht;
 pub mod rimelabs;
+pub mod ultravox;
 pub mod verbio;
 pub mod whisper;
\ No newline at end of file
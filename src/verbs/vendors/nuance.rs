@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::verbs::vendors::vad::Vad;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NuanceRecognizer {
 
@@ -33,6 +34,7 @@ pub struct NuanceRecognizer {
 
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NuanceOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -109,6 +111,7 @@ pub struct NuanceOptions {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NuanceResource {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -137,6 +140,7 @@ pub struct NuanceResource {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NuanceExternalReference {
     #[serde(rename = "type")]
@@ -157,6 +161,7 @@ pub struct NuanceExternalReference {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NuanceFormatting {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -167,6 +172,7 @@ pub struct NuanceFormatting {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum NuanceReferenceType {
     UndefinedResourceType,
@@ -179,6 +185,7 @@ pub enum NuanceReferenceType {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum NuanceReusePolicy {
     UndefinedReuse,
@@ -187,6 +194,7 @@ pub enum NuanceReusePolicy {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum NuanceWeightName {
     DefaultWeight,
@@ -198,6 +206,7 @@ pub enum NuanceWeightName {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum NuanceResultType {
     Final,
@@ -206,6 +215,7 @@ pub enum NuanceResultType {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum NuanceUtteranceDetectionMode {
     Single,
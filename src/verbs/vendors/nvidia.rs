@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::verbs::vendors::vad::Vad;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NvidiaRecognizer {
 
@@ -39,6 +40,7 @@ pub struct NvidiaRecognizer {
 
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct NvidiaOptions {
     pub riva_uri: String,
@@ -1,9 +1,258 @@
 //todo https://docs.jambonz.org/verbs/verbs/recognizer#openaioptions
 
+use crate::verbs::llm::{LlmAuth, LlmTool};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct OpenaiRecognizer {
     
-}
\ No newline at end of file
+}
+
+/// OpenAI Realtime API speech-to-speech options for the llm verb.
+/// https://docs.jambonz.org/verbs/verbs/llm
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OpenaiLlm {
+    pub model: String,
+
+    pub auth: LlmAuth,
+
+    pub llm_options: OpenaiLlmOptions,
+}
+
+impl OpenaiLlm {
+    pub fn new(model: String, api_key: String) -> OpenaiLlm {
+        OpenaiLlm {
+            model,
+            auth: LlmAuth::new(api_key),
+            llm_options: OpenaiLlmOptions::new(),
+        }
+    }
+
+    pub fn response_create(&mut self, response_create: Option<OpenaiResponseCreate>) -> &mut OpenaiLlm {
+        self.llm_options.response_create = response_create;
+        self
+    }
+
+    pub fn session_update(&mut self, session_update: Option<OpenaiSessionUpdate>) -> &mut OpenaiLlm {
+        self.llm_options.session_update = session_update;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OpenaiLlmOptions {
+    /// Sent as a `response.create` client event when the session starts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_create: Option<OpenaiResponseCreate>,
+
+    /// Sent as a `session.update` client event when the session starts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_update: Option<OpenaiSessionUpdate>,
+}
+
+impl Default for OpenaiLlmOptions {
+    fn default() -> OpenaiLlmOptions {
+        OpenaiLlmOptions::new()
+    }
+}
+
+impl OpenaiLlmOptions {
+    pub fn new() -> OpenaiLlmOptions {
+        OpenaiLlmOptions {
+            response_create: None,
+            session_update: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OpenaiResponseCreate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modalities: Option<Vec<OpenaiModality>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u16>,
+}
+
+impl OpenaiResponseCreate {
+    pub fn new(instructions: String) -> OpenaiResponseCreate {
+        OpenaiResponseCreate {
+            modalities: Some(vec![OpenaiModality::Text, OpenaiModality::Audio]),
+            instructions: Some(instructions),
+            voice: None,
+            temperature: None,
+            max_output_tokens: None,
+        }
+    }
+
+    pub fn modalities(&mut self, modalities: Option<Vec<OpenaiModality>>) -> &mut OpenaiResponseCreate {
+        self.modalities = modalities;
+        self
+    }
+
+    pub fn voice(&mut self, voice: Option<String>) -> &mut OpenaiResponseCreate {
+        self.voice = voice;
+        self
+    }
+
+    pub fn temperature(&mut self, temperature: Option<f32>) -> &mut OpenaiResponseCreate {
+        self.temperature = temperature;
+        self
+    }
+
+    pub fn max_output_tokens(&mut self, max_output_tokens: Option<u16>) -> &mut OpenaiResponseCreate {
+        self.max_output_tokens = max_output_tokens;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OpenaiSessionUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<LlmTool>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_audio_transcription: Option<OpenaiInputAudioTranscription>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn_detection: Option<OpenaiTurnDetection>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_output_tokens: Option<u16>,
+}
+
+impl Default for OpenaiSessionUpdate {
+    fn default() -> OpenaiSessionUpdate {
+        OpenaiSessionUpdate::new()
+    }
+}
+
+impl OpenaiSessionUpdate {
+    pub fn new() -> OpenaiSessionUpdate {
+        OpenaiSessionUpdate {
+            instructions: None,
+            voice: None,
+            tools: None,
+            tool_choice: None,
+            input_audio_transcription: None,
+            turn_detection: None,
+            temperature: None,
+            max_response_output_tokens: None,
+        }
+    }
+
+    pub fn instructions(&mut self, instructions: Option<String>) -> &mut OpenaiSessionUpdate {
+        self.instructions = instructions;
+        self
+    }
+
+    pub fn voice(&mut self, voice: Option<String>) -> &mut OpenaiSessionUpdate {
+        self.voice = voice;
+        self
+    }
+
+    pub fn tools(&mut self, tools: Option<Vec<LlmTool>>) -> &mut OpenaiSessionUpdate {
+        self.tools = tools;
+        self
+    }
+
+    pub fn tool_choice(&mut self, tool_choice: Option<String>) -> &mut OpenaiSessionUpdate {
+        self.tool_choice = tool_choice;
+        self
+    }
+
+    pub fn input_audio_transcription(
+        &mut self,
+        transcription: Option<OpenaiInputAudioTranscription>,
+    ) -> &mut OpenaiSessionUpdate {
+        self.input_audio_transcription = transcription;
+        self
+    }
+
+    pub fn turn_detection(&mut self, turn_detection: Option<OpenaiTurnDetection>) -> &mut OpenaiSessionUpdate {
+        self.turn_detection = turn_detection;
+        self
+    }
+
+    pub fn temperature(&mut self, temperature: Option<f32>) -> &mut OpenaiSessionUpdate {
+        self.temperature = temperature;
+        self
+    }
+
+    pub fn max_response_output_tokens(
+        &mut self,
+        max_response_output_tokens: Option<u16>,
+    ) -> &mut OpenaiSessionUpdate {
+        self.max_response_output_tokens = max_response_output_tokens;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum OpenaiModality {
+    Text,
+    Audio,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OpenaiInputAudioTranscription {
+    pub model: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OpenaiTurnDetection {
+    #[serde(rename = "type")]
+    pub detection_type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix_padding_ms: Option<u16>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub silence_duration_ms: Option<u16>,
+}
+
+impl OpenaiTurnDetection {
+    pub fn server_vad() -> OpenaiTurnDetection {
+        OpenaiTurnDetection {
+            detection_type: "server_vad".to_string(),
+            threshold: None,
+            prefix_padding_ms: None,
+            silence_duration_ms: None,
+        }
+    }
+}
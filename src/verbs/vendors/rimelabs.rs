@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 //todo https://docs.jambonz.org/verbs/verbs/synthesizer#rimelabs
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct RimelabsSynthesizer {
 
 }
\ No newline at end of file
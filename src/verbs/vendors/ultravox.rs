@@ -0,0 +1,154 @@
+use crate::verbs::llm::LlmAuth;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Ultravox speech-to-speech options for the llm verb.
+/// `llmOptions` is passed through as the Ultravox create call request.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UltravoxLlm {
+    pub model: String,
+
+    pub auth: LlmAuth,
+
+    pub llm_options: UltravoxLlmOptions,
+}
+
+impl UltravoxLlm {
+    pub fn new(model: String, api_key: String) -> UltravoxLlm {
+        UltravoxLlm {
+            model,
+            auth: LlmAuth::new(api_key),
+            llm_options: UltravoxLlmOptions::new(),
+        }
+    }
+
+    pub fn llm_options(&mut self, llm_options: UltravoxLlmOptions) -> &mut UltravoxLlm {
+        self.llm_options = llm_options;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UltravoxLlmOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_speaker: Option<UltravoxFirstSpeaker>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_hint: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_tools: Option<Vec<UltravoxSelectedTool>>,
+}
+
+impl Default for UltravoxLlmOptions {
+    fn default() -> UltravoxLlmOptions {
+        UltravoxLlmOptions::new()
+    }
+}
+
+impl UltravoxLlmOptions {
+    pub fn new() -> UltravoxLlmOptions {
+        UltravoxLlmOptions {
+            system_prompt: None,
+            first_speaker: None,
+            voice: None,
+            temperature: None,
+            language_hint: None,
+            selected_tools: None,
+        }
+    }
+
+    pub fn system_prompt(&mut self, system_prompt: Option<String>) -> &mut UltravoxLlmOptions {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    pub fn first_speaker(&mut self, first_speaker: Option<UltravoxFirstSpeaker>) -> &mut UltravoxLlmOptions {
+        self.first_speaker = first_speaker;
+        self
+    }
+
+    pub fn voice(&mut self, voice: Option<String>) -> &mut UltravoxLlmOptions {
+        self.voice = voice;
+        self
+    }
+
+    pub fn temperature(&mut self, temperature: Option<f32>) -> &mut UltravoxLlmOptions {
+        self.temperature = temperature;
+        self
+    }
+
+    pub fn language_hint(&mut self, language_hint: Option<String>) -> &mut UltravoxLlmOptions {
+        self.language_hint = language_hint;
+        self
+    }
+
+    pub fn selected_tools(&mut self, tools: Option<Vec<UltravoxSelectedTool>>) -> &mut UltravoxLlmOptions {
+        self.selected_tools = tools;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum UltravoxFirstSpeaker {
+    #[serde(rename = "FIRST_SPEAKER_AGENT")]
+    Agent,
+    #[serde(rename = "FIRST_SPEAKER_USER")]
+    User,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UltravoxSelectedTool {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temporary_tool: Option<UltravoxTemporaryTool>,
+}
+
+/// A tool defined inline for this call. Tools with a `client` implementation
+/// are delivered to the llm verb's `toolHook`.
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UltravoxTemporaryTool {
+    pub model_tool_name: String,
+
+    pub description: String,
+
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dynamic_parameters: Vec<UltravoxDynamicParameter>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UltravoxDynamicParameter {
+    pub name: String,
+
+    pub location: String,
+
+    pub schema: Value,
+
+    pub required: bool,
+}
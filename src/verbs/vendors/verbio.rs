@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 //todo https://docs.jambonz.org/verbs/verbs/synthesizer#verbio
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct VerbioSynthesizer {
 
 }
\ No newline at end of file
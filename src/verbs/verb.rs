@@ -1,4 +1,5 @@
 use crate::listen::Listen;
+use crate::payload::rest::HookKind;
 use crate::verbs::conference::Conference;
 use crate::verbs::config::Config;
 use crate::verbs::dequeue::Dequeue;
@@ -11,6 +12,7 @@ use crate::verbs::gather::Gather;
 use crate::verbs::hangup::Hangup;
 use crate::verbs::leave::Leave;
 use crate::verbs::lex::Lex;
+use crate::verbs::llm::Llm;
 use crate::verbs::message::Message;
 use crate::verbs::pause::Pause;
 use crate::verbs::play::Play;
@@ -22,16 +24,27 @@ use crate::verbs::siprec::SipRec;
 use crate::verbs::siprefer::SipRefer;
 use crate::verbs::tag::Tag;
 use crate::verbs::transcribe::Transcribe;
-use serde::{Deserialize, Serialize};
+use crate::shared::strict::{tagged, unknown, variant, Known};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-#[derive(Serialize, Deserialize, Clone)]
+static VERB_ID_SEQ: AtomicU64 = AtomicU64::new(1);
+
+// Boxing the larger verbs would change every variant's public type.
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Clone, strum::IntoStaticStr)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "verb")]
+#[strum(serialize_all = "camelCase")]
 pub enum Verb {
     Conference(Conference),
     Config(Config),
     Dequeue(Dequeue),
     Dial(Dial),
+    #[serde(rename = "dialogflow")]
+    #[strum(serialize = "dialogflow")]
     DialogFlow(DialogFlow),
     Dub(DubData),
     Dtmf(Dtmf),
@@ -41,8 +54,10 @@ pub enum Verb {
     Leave(Leave),
     Lex(Lex),
     Listen(Listen),
+    Llm(Llm),
     Message(Message),
     #[serde(rename = "sip:decline")]
+    #[strum(serialize = "sip:decline")]
     SipDecline(SipDecline),
     SipRec(SipRec),
     Pause(Pause),
@@ -51,9 +66,61 @@ pub enum Verb {
     Rasa(Rasa),
     Say(Say),
     #[serde(rename = "sip:refer")]
+    #[strum(serialize = "sip:refer")]
     SipRefer(SipRefer),
     Tag(Tag),
     Transcribe(Transcribe),
+    /// A verb this crate does not model, kept as the raw JSON.
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+/// Falls back to `Unknown` only for verb names not modelled above; a bad
+/// body for a known verb is an error.
+impl<'de> Deserialize<'de> for Verb {
+    fn deserialize<D>(deserializer: D) -> Result<Verb, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (name, body) = tagged(deserializer, "verb")?;
+        Ok(match name.as_str() {
+            "conference" => Verb::Conference(variant(body)?),
+            "config" => Verb::Config(variant(body)?),
+            "dequeue" => Verb::Dequeue(variant(body)?),
+            "dial" => Verb::Dial(variant(body)?),
+            "dialogflow" | "dialogFlow" => Verb::DialogFlow(variant(body)?),
+            "dub" => Verb::Dub(variant(body)?),
+            "dtmf" => Verb::Dtmf(variant(body)?),
+            "enqueue" => Verb::Enqueue(variant(body)?),
+            "gather" => Verb::Gather(variant(body)?),
+            "hangup" => Verb::Hangup(variant(body)?),
+            "leave" => Verb::Leave(variant(body)?),
+            "lex" => Verb::Lex(variant(body)?),
+            "listen" => Verb::Listen(variant(body)?),
+            "llm" => Verb::Llm(variant(body)?),
+            "message" => Verb::Message(variant(body)?),
+            "sip:decline" => Verb::SipDecline(variant(body)?),
+            "sipRec" => Verb::SipRec(variant(body)?),
+            "pause" => Verb::Pause(variant(body)?),
+            "play" => Verb::Play(variant(body)?),
+            "redirect" => Verb::Redirect(variant(body)?),
+            "rasa" => Verb::Rasa(variant(body)?),
+            "say" => Verb::Say(variant(body)?),
+            "sip:refer" => Verb::SipRefer(variant(body)?),
+            "tag" => Verb::Tag(variant(body)?),
+            "transcribe" => Verb::Transcribe(variant(body)?),
+            _ => Verb::Unknown(unknown("verb", name, body)),
+        })
+    }
+}
+
+impl Known for Verb {
+    fn unknown(&self) -> Option<&Value> {
+        match self {
+            Verb::Unknown(verb) => Some(verb),
+            _ => None,
+        }
+    }
 }
 
 impl Into<Vec<Verb>> for Verb {
@@ -61,3 +128,152 @@ impl Into<Vec<Verb>> for Verb {
         vec![self]
     }
 }
+
+impl Verb {
+    /// The verb name as sent to jambonz, e.g. "say" or "sip:decline".
+    /// Returns "unknown" for `Verb::Unknown`.
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+
+    /// The `id` jambonz echoes back in `verb:status` events for this verb.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Verb::Conference(verb) => verb.id.as_deref(),
+            Verb::Config(verb) => verb.id.as_deref(),
+            Verb::Dequeue(verb) => verb.id.as_deref(),
+            Verb::Dial(verb) => verb.id.as_deref(),
+            Verb::DialogFlow(verb) => verb.id.as_deref(),
+            Verb::Dub(verb) => verb.id.as_deref(),
+            Verb::Dtmf(verb) => verb.id.as_deref(),
+            Verb::Enqueue(verb) => verb.id.as_deref(),
+            Verb::Gather(verb) => verb.id.as_deref(),
+            Verb::Hangup(verb) => verb.id.as_deref(),
+            Verb::Leave(verb) => verb.id.as_deref(),
+            Verb::Lex(verb) => verb.id.as_deref(),
+            Verb::Listen(verb) => verb.id.as_deref(),
+            Verb::Llm(verb) => verb.id.as_deref(),
+            Verb::Message(verb) => verb.id.as_deref(),
+            Verb::SipDecline(verb) => verb.id.as_deref(),
+            // SIPREC options are sent in `config`, which does not allow an id.
+            Verb::SipRec(_) => None,
+            Verb::Pause(verb) => verb.id.as_deref(),
+            Verb::Play(verb) => verb.id.as_deref(),
+            Verb::Redirect(verb) => verb.id.as_deref(),
+            Verb::Rasa(verb) => verb.id.as_deref(),
+            Verb::Say(verb) => verb.id.as_deref(),
+            Verb::SipRefer(verb) => verb.id.as_deref(),
+            Verb::Tag(verb) => verb.id.as_deref(),
+            Verb::Transcribe(verb) => verb.id.as_deref(),
+            Verb::Unknown(verb) => verb.get("id").and_then(Value::as_str),
+        }
+    }
+
+    pub fn set_id(&mut self, id: Option<String>) {
+        match self {
+            Verb::Conference(verb) => verb.id = id,
+            Verb::Config(verb) => verb.id = id,
+            Verb::Dequeue(verb) => verb.id = id,
+            Verb::Dial(verb) => verb.id = id,
+            Verb::DialogFlow(verb) => verb.id = id,
+            Verb::Dub(verb) => verb.id = id,
+            Verb::Dtmf(verb) => verb.id = id,
+            Verb::Enqueue(verb) => verb.id = id,
+            Verb::Gather(verb) => verb.id = id,
+            Verb::Hangup(verb) => verb.id = id,
+            Verb::Leave(verb) => verb.id = id,
+            Verb::Lex(verb) => verb.id = id,
+            Verb::Listen(verb) => verb.id = id,
+            Verb::Llm(verb) => verb.id = id,
+            Verb::Message(verb) => verb.id = id,
+            Verb::SipDecline(verb) => verb.id = id,
+            Verb::SipRec(_) => {}
+            Verb::Pause(verb) => verb.id = id,
+            Verb::Play(verb) => verb.id = id,
+            Verb::Redirect(verb) => verb.id = id,
+            Verb::Rasa(verb) => verb.id = id,
+            Verb::Say(verb) => verb.id = id,
+            Verb::SipRefer(verb) => verb.id = id,
+            Verb::Tag(verb) => verb.id = id,
+            Verb::Transcribe(verb) => verb.id = id,
+            Verb::Unknown(verb) => {
+                if let Some(verb) = verb.as_object_mut() {
+                    match id {
+                        Some(id) => verb.insert("id".to_string(), Value::String(id)),
+                        None => verb.remove("id"),
+                    };
+                }
+            }
+        }
+    }
+
+    /// Assigns a process-unique id (e.g. "say-12") if the verb has none,
+    /// and returns the verb's id.
+    pub fn ensure_id(&mut self) -> String {
+        if let Some(id) = self.id() {
+            return id.to_string();
+        }
+        let id = format!("{}-{}", self.name(), VERB_ID_SEQ.fetch_add(1, Ordering::Relaxed));
+        self.set_id(Some(id.clone()));
+        id
+    }
+
+    /// The hooks set on this verb and the objects nested in it, as
+    /// `(hook, kind)` pairs. A hook is named by the object that owns it, e.g.
+    /// `("/menu", HookKind::verb("gather", "actionHook"))` or, for a dial's
+    /// answering machine detection, `("/amd", HookKind::verb("amd", "actionHook"))`.
+    pub fn hooks(&self) -> Vec<(String, HookKind)> {
+        let mut hooks = vec![];
+        if let Ok(verb) = serde_json::to_value(self) {
+            collect_hooks(self.name(), &verb, &mut hooks);
+        }
+        hooks
+    }
+}
+
+fn collect_hooks(owner: &str, value: &Value, hooks: &mut Vec<(String, HookKind)>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let url = match value {
+                    _ if !key.ends_with("Hook") => None,
+                    Value::String(url) => Some(url.as_str()),
+                    hook => hook.get("url").and_then(Value::as_str),
+                };
+                match url {
+                    Some(url) => hooks.push((url.to_string(), HookKind::verb(owner, key))),
+                    None => collect_hooks(key, value, hooks),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_hooks(owner, item, hooks);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn verb_ids() {
+    use crate::payload::builder::VerbTrait;
+    use crate::payload::ws::Verbs;
+
+    let mut verbs = Verbs::new("1234");
+    verbs
+        .say_text("Welcome to Callable")
+        .with_id("welcome")
+        .play_url("https://example.com/hold.mp3")
+        .hangup()
+        .with_generated_ids();
+
+    assert_eq!(verbs.find_by_id("welcome").map(Verb::name), Some("say"));
+    let play_id = verbs.data[1].id().unwrap().to_string();
+    assert!(play_id.starts_with("play-"));
+    assert_eq!(verbs.find_by_id(&play_id).map(Verb::name), Some("play"));
+    assert_ne!(verbs.data[1].id(), verbs.data[2].id());
+
+    let value: serde_json::Value = serde_json::from_str(&verbs.as_ack_reply().json()).unwrap();
+    assert_eq!(value["data"][0]["id"], "welcome");
+}